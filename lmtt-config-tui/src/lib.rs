@@ -73,7 +73,9 @@ fn apply_theme_on_exit() -> Result<()> {
         eprint!("{}", String::from_utf8_lossy(&output.stderr));
     }
 
-    if !output.status.success() {
+    // Exit status 2 is a partial failure: the theme was applied and the
+    // failing modules are already listed in the output above.
+    if !output.status.success() && output.status.code() != Some(2) {
         anyhow::bail!("Failed to apply theme");
     }
 
@@ -1,9 +1,26 @@
 use crate::{Config, Result, ThemeMode};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+/// Outcome of the most recent `lmtt switch`, kept so status consumers (the
+/// waybar output) can report failures after the switching process exited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchRecord {
+    pub mode: ThemeMode,
+    pub succeeded: usize,
+    #[serde(default)]
+    pub failed: Vec<FailedModule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedModule {
+    pub name: String,
+    pub error: String,
+}
+
 pub struct Cache {
     cache_dir: PathBuf,
     // Memoized wallpaper hashes: a cache-miss switch hashes the wallpaper in
@@ -54,6 +71,33 @@ impl Cache {
         }
     }
 
+    /// When the theme state was last recorded, i.e. the last successful
+    /// switch. None if no switch has been recorded yet.
+    pub async fn theme_state_modified(&self) -> Option<std::time::SystemTime> {
+        let state_file = self.cache_dir.join("theme_state");
+        tokio::fs::metadata(&state_file).await.ok()?.modified().ok()
+    }
+
+    /// Lock file held by `lmtt switch` for the duration of a switch.
+    pub fn lock_path(&self) -> PathBuf {
+        self.cache_dir.join("lmtt.lock")
+    }
+
+    /// The last recorded switch outcome. A missing or unreadable record is
+    /// None — it is informational only.
+    pub async fn get_last_switch(&self) -> Option<SwitchRecord> {
+        let content = tokio::fs::read_to_string(self.cache_dir.join("last_switch.json"))
+            .await
+            .ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Record the outcome of a switch (including one where every module failed).
+    pub async fn set_last_switch(&self, record: &SwitchRecord) -> Result<()> {
+        let json = serde_json::to_string(record)?;
+        crate::fsutil::write_atomic(&self.cache_dir.join("last_switch.json"), json).await
+    }
+
     /// Set theme state
     pub async fn set_theme_state(&self, mode: ThemeMode) -> Result<()> {
         let state_file = self.cache_dir.join("theme_state");
@@ -40,6 +40,23 @@ pub fn hex_to_rgb(hex: &str) -> Result<(u8, u8, u8), String> {
     }
 }
 
+/// Blend two hex colors in Oklab, where equal steps of `t` look like equal
+/// steps of change (sRGB blends sag through muddy grays). `t` is clamped to
+/// 0..=1; the result is `#rrggbb`, clipped to the sRGB gamut.
+pub fn mix_oklab(from: &str, to: &str, t: f32) -> Result<String, String> {
+    use palette::{Clamp, FromColor, Mix, Oklab, Srgb};
+
+    let oklab = |hex: &str| {
+        hex_to_rgb(hex).map(|(r, g, b)| Oklab::from_color(Srgb::new(r, g, b).into_format::<f32>()))
+    };
+    let mixed = oklab(from)?.mix(oklab(to)?, t.clamp(0.0, 1.0));
+    let (r, g, b) = Srgb::from_color(mixed)
+        .clamp()
+        .into_format::<u8>()
+        .into_components();
+    Ok(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
 /// GNOME `org.gnome.desktop.interface accent-color` names. The portal exposes
 /// accent as `(ddd)`; the writable host key is this enum, not arbitrary RGB.
 const GNOME_ACCENTS: &[(&str, u8, u8, u8)] = &[
@@ -130,6 +147,19 @@ mod tests {
         assert!(hex_to_rgb("#€€€€€€").is_err());
     }
 
+    #[test]
+    fn test_mix_oklab() {
+        assert_eq!(mix_oklab("#123456", "#abcdef", 0.0).unwrap(), "#123456");
+        assert_eq!(mix_oklab("#123456", "#abcdef", 1.0).unwrap(), "#abcdef");
+        // Oklab L is perceptual: halfway from black to white is a neutral
+        // gray near #636363, not sRGB's arithmetic #808080
+        let mid = mix_oklab("#000000", "#ffffff", 0.5).unwrap();
+        let (r, g, b) = hex_to_rgb(&mid).unwrap();
+        assert!(r == g && g == b, "not neutral: {mid}");
+        assert!((0x60..0x70).contains(&r), "unexpected midpoint: {mid}");
+        assert!(mix_oklab("#xyz", "#ffffff", 0.5).is_err());
+    }
+
     #[test]
     fn test_parse_v3_actual_format() {
         let json = r##"{"colors":{"dark":{"primary":"#d0bcff","secondary":"#ccc2dc"},"light":{"primary":"#6750a4","secondary":"#625b71"}}}"##;
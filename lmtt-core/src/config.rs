@@ -14,6 +14,12 @@ pub struct Config {
     #[serde(default)]
     pub performance: PerformanceConfig,
 
+    #[serde(default)]
+    pub switch: SwitchConfig,
+
+    #[serde(default)]
+    pub wallpaper: WallpaperConfig,
+
     #[serde(default)]
     pub modules: ModuleConfig,
 
@@ -72,6 +78,58 @@ pub struct PerformanceConfig {
     pub slow_module_threshold: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SwitchConfig {
+    /// Fade live-recolorable apps from the old palette to the new one over
+    /// this many milliseconds. 0 switches instantly.
+    #[serde(default)]
+    pub transition_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperConfig {
+    /// Daemon that shows the appearance-profiles wallpaper on each output.
+    #[serde(default)]
+    pub backend: WallpaperBackend,
+
+    /// swww `--transition-type` (simple, fade, wipe, grow, ...).
+    #[serde(default = "default_transition_type")]
+    pub transition_type: String,
+
+    /// swww `--transition-duration`, in seconds.
+    #[serde(default = "default_transition_duration")]
+    pub transition_duration: f32,
+
+    /// swww `--transition-fps`.
+    #[serde(default = "default_transition_fps")]
+    pub transition_fps: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WallpaperBackend {
+    /// Whichever of swww, hyprpaper and swaybg is running, in that order.
+    #[default]
+    Auto,
+    Swww,
+    Swaybg,
+    Hyprpaper,
+    /// Leave the desktop wallpaper alone.
+    None,
+}
+
+impl std::fmt::Display for WallpaperBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WallpaperBackend::Auto => write!(f, "auto"),
+            WallpaperBackend::Swww => write!(f, "swww"),
+            WallpaperBackend::Swaybg => write!(f, "swaybg"),
+            WallpaperBackend::Hyprpaper => write!(f, "hyprpaper"),
+            WallpaperBackend::None => write!(f, "none"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ModuleConfig {
     #[serde(flatten)]
@@ -195,6 +253,17 @@ impl Default for PerformanceConfig {
     }
 }
 
+impl Default for WallpaperConfig {
+    fn default() -> Self {
+        Self {
+            backend: WallpaperBackend::Auto,
+            transition_type: default_transition_type(),
+            transition_duration: default_transition_duration(),
+            transition_fps: default_transition_fps(),
+        }
+    }
+}
+
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
@@ -243,6 +312,18 @@ fn default_slow_threshold() -> u64 {
     250
 }
 
+fn default_transition_type() -> String {
+    "fade".to_string()
+}
+
+fn default_transition_duration() -> f32 {
+    1.0
+}
+
+fn default_transition_fps() -> u32 {
+    60
+}
+
 fn default_cache_dir() -> String {
     crate::paths::user_cache_dir()
         .map(|p| p.to_string_lossy().into_owned())
@@ -308,6 +389,15 @@ impl Config {
             ("performance", "timeout") => "Maximum seconds to wait for each module to complete (default: 10)",
             ("performance", "slow_module_threshold") => "Log warning if any module takes longer than this in milliseconds (default: 250)",
 
+            // Switch
+            ("switch", "transition_ms") => "Fade terminals, borders and tmux to the new colors over this many milliseconds (0 = instant)",
+
+            // Wallpaper
+            ("wallpaper", "backend") => "Wallpaper daemon for the appearance-profiles wallpaper: auto, swww, swaybg, hyprpaper or none",
+            ("wallpaper", "transition_type") => "swww transition when the wallpaper changes (simple, fade, wipe, grow, ...)",
+            ("wallpaper", "transition_duration") => "swww transition duration in seconds",
+            ("wallpaper", "transition_fps") => "swww transition frame rate",
+
             // Cache
             ("cache", "enabled") => "Cache matugen color generation results to speed up repeated theme switches",
             ("cache", "dir") => "Directory to store cached color schemes (supports ~)",
@@ -465,6 +555,52 @@ impl Config {
             self.performance.slow_module_threshold
         ));
 
+        // Switch section
+        output.push_str("[switch]\n");
+        output.push_str(&format!(
+            "# {}\n",
+            Self::get_field_description("switch", "transition_ms")
+        ));
+        output.push_str(&format!(
+            "transition_ms = {}\n\n",
+            self.switch.transition_ms
+        ));
+
+        // Wallpaper section
+        output.push_str("[wallpaper]\n");
+        output.push_str(&format!(
+            "# {}\n",
+            Self::get_field_description("wallpaper", "backend")
+        ));
+        output.push_str(&format!("backend = \"{}\"\n\n", self.wallpaper.backend));
+
+        output.push_str(&format!(
+            "# {}\n",
+            Self::get_field_description("wallpaper", "transition_type")
+        ));
+        output.push_str(&format!(
+            "transition_type = {}\n\n",
+            Self::toml_quote(&self.wallpaper.transition_type)
+        ));
+
+        output.push_str(&format!(
+            "# {}\n",
+            Self::get_field_description("wallpaper", "transition_duration")
+        ));
+        output.push_str(&format!(
+            "transition_duration = {:?}\n\n",
+            self.wallpaper.transition_duration
+        ));
+
+        output.push_str(&format!(
+            "# {}\n",
+            Self::get_field_description("wallpaper", "transition_fps")
+        ));
+        output.push_str(&format!(
+            "transition_fps = {}\n\n",
+            self.wallpaper.transition_fps
+        ));
+
         // Modules section
         output.push_str("[modules]\n");
         if self.modules.modules.is_empty() {
@@ -778,6 +914,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wallpaper_section_defaults_missing_fields() {
+        let config: Config = toml::from_str("[wallpaper]\nbackend = \"swaybg\"\n").unwrap();
+        assert_eq!(config.wallpaper.backend, WallpaperBackend::Swaybg);
+        assert_eq!(config.wallpaper.transition_type, "fade");
+        assert_eq!(Config::default().wallpaper.backend, WallpaperBackend::Auto);
+    }
+
     #[test]
     fn expand_env_vars_no_self_reference_loop() {
         std::env::set_var("LMTT_SELF", "${LMTT_SELF}");
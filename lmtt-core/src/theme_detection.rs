@@ -143,6 +143,8 @@ pub fn detect_vscode_themes() -> Vec<String> {
         "Default Dark Modern".to_string(),
         "Default Light Modern".to_string(),
         "Default High Contrast".to_string(),
+        // Generates the lmtt-theme extension from the palette
+        "lmtt".to_string(),
     ];
 
     // Extensions live in ~/.<editor>/extensions, not under ~/.config
@@ -238,6 +240,8 @@ pub fn detect_neovim_colorschemes() -> Vec<String> {
         "habamax".to_string(),
         "darkplus".to_string(),
         "oxocarbon".to_string(),
+        // Generated by the nvim module from the current palette
+        "lmtt".to_string(),
     ];
 
     if let Some(home) = dirs::home_dir() {
@@ -106,6 +106,30 @@ impl ColorScheme {
         css
     }
 
+    /// A frame `t` (0..=1) of the way from `self` to `to`, blended per token
+    /// in Oklab. Tokens missing from either side, or not hex, take `to`'s
+    /// value. The mode flips at the midpoint so mode-keyed fallbacks and
+    /// fixed hues switch when the frame is closest to neutral.
+    pub fn interpolate(&self, to: &ColorScheme, t: f32) -> ColorScheme {
+        // Exact endpoints: an Oklab round trip can move a channel by one.
+        if t <= 0.0 {
+            return self.clone();
+        }
+        if t >= 1.0 {
+            return to.clone();
+        }
+        let mut frame = ColorScheme::new(if t < 0.5 { self.mode } else { to.mode });
+        for (key, target) in &to.colors {
+            let value = self
+                .colors
+                .get(key)
+                .and_then(|from| crate::colors::mix_oklab(from, target, t).ok())
+                .unwrap_or_else(|| target.clone());
+            frame.colors.insert(key.clone(), value);
+        }
+        frame
+    }
+
     /// Get primary color
     pub fn primary(&self) -> Option<&String> {
         self.get("primary")
@@ -116,3 +140,30 @@ impl ColorScheme {
         self.get("surface")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_returns_endpoints_and_flips_mode_at_midpoint() {
+        let mut dark = ColorScheme::new(ThemeMode::Dark);
+        dark.set("primary".into(), "#9ccaff".into());
+        dark.set("surface".into(), "#111418".into());
+        let mut light = ColorScheme::new(ThemeMode::Light);
+        light.set("primary".into(), "#3a5f8a".into());
+        light.set("surface".into(), "#f8f9ff".into());
+
+        let start = dark.interpolate(&light, 0.0);
+        assert_eq!(start.mode, ThemeMode::Dark);
+        assert_eq!(start.colors, dark.colors);
+        let end = dark.interpolate(&light, 1.0);
+        assert_eq!(end.mode, ThemeMode::Light);
+        assert_eq!(end.colors, light.colors);
+
+        let mid = dark.interpolate(&light, 0.5);
+        assert_eq!(mid.mode, ThemeMode::Light);
+        assert_ne!(mid.colors["surface"], dark.colors["surface"]);
+        assert_ne!(mid.colors["surface"], light.colors["surface"]);
+    }
+}
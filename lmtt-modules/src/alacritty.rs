@@ -0,0 +1,384 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+crate::register_module!(AlacrittyModule);
+
+pub struct AlacrittyModule;
+
+impl Default for AlacrittyModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlacrittyModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const SLOT_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn alacritty_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("alacritty"))
+}
+
+/// The config file alacritty would load, in its own lookup order (the
+/// `$HOME/.config` fallbacks are the same paths on most systems).
+fn find_alacritty_toml() -> Result<Option<PathBuf>> {
+    let config_dir = dirs::config_dir().ok_or(lmtt_core::Error::Config("No config dir".into()))?;
+    let mut candidates = vec![
+        config_dir.join("alacritty").join("alacritty.toml"),
+        config_dir.join("alacritty.toml"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".alacritty.toml"));
+    }
+    Ok(candidates.into_iter().find(|p| p.exists()))
+}
+
+/// A YAML config alacritty no longer reads (0.13 switched to TOML), when
+/// there is no TOML config next to it.
+fn legacy_yml() -> Option<PathBuf> {
+    let dir = alacritty_dir().ok()?;
+    let yml = dir.join("alacritty.yml");
+    (yml.exists() && !dir.join("alacritty.toml").exists()).then_some(yml)
+}
+
+fn legacy_yml_message(yml: &std::path::Path) -> String {
+    format!(
+        "{} is ignored by alacritty >= 0.13; run `alacritty migrate`, then `lmtt setup`",
+        yml.display()
+    )
+}
+
+/// How the colors file is named in `import`: `~/`-relative when it lives
+/// under $HOME (alacritty expands `~`), so the config stays portable.
+fn import_entry(colors_file: &std::path::Path) -> String {
+    match dirs::home_dir().and_then(|home| colors_file.strip_prefix(home).ok().map(PathBuf::from)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => colors_file.display().to_string(),
+    }
+}
+
+fn colors_toml(scheme: &ColorScheme) -> String {
+    let term = terminal::term_colors(scheme);
+    // Dim colors sit a third of the way from each slot toward the background
+    // in Oklab. This is lmtt's choice: alacritty's own fallback scales RGB
+    // toward black, which muddies light-mode palettes.
+    let dim = |color: &str| {
+        lmtt_core::colors::mix_oklab(color, &term.background, 0.33)
+            .unwrap_or_else(|_| color.to_string())
+    };
+
+    let mut content = String::new();
+    content.push_str("# Alacritty colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n", scheme.mode));
+
+    let section = |content: &mut String, name: &str, entries: &[(&str, String)]| {
+        content.push_str(&format!("\n[colors.{}]\n", name));
+        for (key, value) in entries {
+            content.push_str(&format!("{} = \"{}\"\n", key, value));
+        }
+    };
+
+    section(
+        &mut content,
+        "primary",
+        &[
+            ("background", term.background.clone()),
+            ("foreground", term.foreground.clone()),
+            ("dim_foreground", dim(&term.foreground)),
+            ("bright_foreground", term.foreground.clone()),
+        ],
+    );
+    section(
+        &mut content,
+        "cursor",
+        &[
+            ("text", term.cursor_fg.clone()),
+            ("cursor", term.cursor_bg.clone()),
+        ],
+    );
+    section(
+        &mut content,
+        "vi_mode_cursor",
+        &[
+            ("text", term.cursor_fg.clone()),
+            ("cursor", term.cursor_bg.clone()),
+        ],
+    );
+    section(
+        &mut content,
+        "selection",
+        &[
+            ("text", term.selection_fg.clone()),
+            ("background", term.selection_bg.clone()),
+        ],
+    );
+    for (name, colors) in [
+        ("normal", term.ansi.clone()),
+        ("bright", term.brights.clone()),
+        ("dim", term.ansi.clone().map(|c| dim(&c))),
+    ] {
+        let entries: Vec<(&str, String)> = SLOT_NAMES.iter().copied().zip(colors).collect();
+        section(&mut content, name, &entries);
+    }
+    content
+}
+
+/// The import array alacritty reads: `[general] import`, or the top-level
+/// `import` of configs written for alacritty < 0.14 (still honored, so
+/// editing it keeps such configs working on older versions too).
+fn import_array(doc: &DocumentMut) -> Option<&Array> {
+    doc.get("general")
+        .and_then(|general| general.get("import"))
+        .or_else(|| doc.get("import"))
+        .and_then(Item::as_array)
+}
+
+fn is_imported(content: &str, entry: &str) -> bool {
+    let Ok(doc) = content.parse::<DocumentMut>() else {
+        return false;
+    };
+    import_array(&doc).is_some_and(|imports| imports.iter().any(|v| v.as_str() == Some(entry)))
+}
+
+fn parse_config(content: &str) -> Result<DocumentMut> {
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| lmtt_core::Error::Module(format!("alacritty.toml does not parse: {}", e)))
+}
+
+/// Append `entry` to the import array, creating `[general] import` if there
+/// is none. Everything else in the file — comments, key order, formatting —
+/// is left as it was. Imports load before the importing file, later ones
+/// winning, so appending lets lmtt's colors override other imported themes
+/// while the user's own `[colors]` still override lmtt.
+fn add_import(content: &str, entry: &str) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    if import_array(&doc).is_some_and(|imports| imports.iter().any(|v| v.as_str() == Some(entry))) {
+        return Ok(content.to_string());
+    }
+
+    let legacy_top_level = doc.get("import").is_some()
+        && doc
+            .get("general")
+            .and_then(|general| general.get("import"))
+            .is_none();
+    let slot = if legacy_top_level {
+        doc.get_mut("import")
+    } else {
+        let general = doc
+            .entry("general")
+            .or_insert_with(|| Item::Table(Table::new()));
+        general.as_table_like_mut().map(|general| {
+            general
+                .entry("import")
+                .or_insert(Item::Value(Array::new().into()))
+        })
+    };
+    let Some(imports) = slot.and_then(Item::as_array_mut) else {
+        return Err(lmtt_core::Error::Module(
+            "alacritty.toml has a `general` or `import` key lmtt can't extend; add the import manually"
+                .into(),
+        ));
+    };
+
+    // Match the layout of the existing entries: one per line copies the
+    // last entry's indentation, inline gets the default `, ` separation.
+    let prefix = imports
+        .iter()
+        .last()
+        .and_then(|last| last.decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .filter(|prefix| prefix.contains('\n'))
+        .map(str::to_string);
+    match prefix {
+        Some(prefix) => {
+            let mut value = Value::from(entry);
+            value.decor_mut().set_prefix(prefix);
+            imports.push_formatted(value);
+        }
+        None => imports.push(entry),
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Remove `entry` from the import array; drops the array when it ends up
+/// empty, and `[general]` too if nothing else is left in it.
+fn remove_import(content: &str, entry: &str) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    let mut changed = false;
+
+    for in_general in [true, false] {
+        let parent: Option<&mut dyn toml_edit::TableLike> = if in_general {
+            doc.get_mut("general").and_then(Item::as_table_like_mut)
+        } else {
+            Some(doc.as_table_mut())
+        };
+        let Some(parent) = parent else {
+            continue;
+        };
+        let Some(imports) = parent.get_mut("import").and_then(Item::as_array_mut) else {
+            continue;
+        };
+        let before = imports.len();
+        imports.retain(|v| v.as_str() != Some(entry));
+        if imports.len() == before {
+            continue;
+        }
+        changed = true;
+        if imports.is_empty() {
+            parent.remove("import");
+        }
+    }
+
+    if !changed {
+        return Ok(content.to_string());
+    }
+    if doc
+        .get("general")
+        .and_then(Item::as_table_like)
+        .is_some_and(|general| general.is_empty())
+    {
+        doc.remove("general");
+    }
+    Ok(doc.to_string())
+}
+
+#[async_trait]
+impl ThemeModule for AlacrittyModule {
+    fn name(&self) -> &'static str {
+        "alacritty"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "alacritty"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = alacritty_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-colors.toml");
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_toml(scheme)).await?;
+        tracing::info!("[Alacritty] Updated colors at {}", colors_file.display());
+
+        // Alacritty watches its config and every import, so running
+        // instances pick up the new file on their own.
+        if let Some(yml) = legacy_yml() {
+            tracing::warn!("[Alacritty] {}", legacy_yml_message(&yml));
+        }
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let Some(config) = find_alacritty_toml()? else {
+            if let Some(yml) = legacy_yml() {
+                tracing::warn!("[Alacritty] {}", legacy_yml_message(&yml));
+            }
+            return Ok(vec![]);
+        };
+
+        let entry = import_entry(&alacritty_dir()?.join("lmtt-colors.toml"));
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = is_imported(&content, &entry);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line: entry,
+            description: "Add LMTT colors to [general] import in alacritty.toml".to_string(),
+            already_included,
+        }])
+    }
+
+    /// `import` is an array, so the colors file is merged into it rather
+    /// than injected as a managed block (a second `import` key, or a second
+    /// `[general]` table, would make the file invalid TOML).
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = add_import(&content, &config_file.include_line)?;
+        if new_content != content {
+            lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = remove_import(&content, &config_file.include_line)?;
+        if new_content == content {
+            return Ok(false);
+        }
+        lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        Ok(true)
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        if let Some(yml) = legacy_yml() {
+            return Err(lmtt_core::Error::Module(legacy_yml_message(&yml)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_import, is_imported, remove_import};
+
+    const ENTRY: &str = "~/.config/alacritty/lmtt-colors.toml";
+
+    #[test]
+    fn import_merges_into_existing_array() {
+        let conf = "# my config\n[general]\nlive_config_reload = true\nimport = [\n    \"~/.config/alacritty/fonts.toml\",\n]\n\n[font]\nsize = 11 # small\n";
+        let merged = add_import(conf, ENTRY).unwrap();
+        assert!(merged.contains(
+            "\"~/.config/alacritty/fonts.toml\",\n    \"~/.config/alacritty/lmtt-colors.toml\","
+        ));
+        assert!(merged.contains("size = 11 # small"));
+        assert!(is_imported(&merged, ENTRY));
+        assert_eq!(add_import(&merged, ENTRY).unwrap(), merged);
+        assert_eq!(remove_import(&merged, ENTRY).unwrap(), conf);
+    }
+
+    #[test]
+    fn import_creates_general_and_cleans_up() {
+        let conf = "[font]\nsize = 11\n";
+        let merged = add_import(conf, ENTRY).unwrap();
+        assert!(merged.contains("[general]\nimport = [\"~/.config/alacritty/lmtt-colors.toml\"]"));
+        assert_eq!(remove_import(&merged, ENTRY).unwrap(), conf);
+    }
+
+    #[test]
+    fn import_extends_legacy_top_level_array() {
+        let conf = "import = [\"~/themes/base.toml\"]\n\n[font]\nsize = 11\n";
+        let merged = add_import(conf, ENTRY).unwrap();
+        assert!(merged.starts_with(
+            "import = [\"~/themes/base.toml\", \"~/.config/alacritty/lmtt-colors.toml\"]\n"
+        ));
+        assert!(!merged.contains("[general]"));
+        assert_eq!(remove_import(&merged, ENTRY).unwrap(), conf);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::shell::{exports, fzf_opts, path_quote, shell_dir, stamp, STAMP_PREFIX};
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::Path;
+
+crate::register_module!(BashModule);
+
+pub struct BashModule;
+
+impl Default for BashModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BashModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const INCLUDE_LINE: &str =
+    "[ -r ~/.config/lmtt/shell/colors.bash ] && source ~/.config/lmtt/shell/colors.bash";
+
+/// Re-source `colors_file` from PROMPT_COMMAND whenever its stamp differs
+/// from the one last sourced. Prepended once, so a re-source doesn't stack
+/// it; works whether PROMPT_COMMAND is a string or, in bash 5.1+, an array.
+fn reload_hook(colors_file: &Path) -> String {
+    format!(
+        "_lmtt_precmd() {{\n    \
+             local stamp\n    \
+             IFS= read -r stamp 2>/dev/null < {colors} || return 0\n    \
+             [[ $stamp == \"{prefix}$_lmtt_stamp\" ]] || source {colors}\n\
+         }}\n\
+         [[ \"${{PROMPT_COMMAND[*]}}\" == *_lmtt_precmd* ]] ||\n    \
+             PROMPT_COMMAND=\"_lmtt_precmd${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}\"\n",
+        prefix = STAMP_PREFIX,
+        colors = path_quote(colors_file),
+    )
+}
+
+fn colors_bash(scheme: &ColorScheme, colors_file: &Path) -> Result<String> {
+    let mut body = exports(scheme, "Bash")?;
+    body.push('\n');
+    body.push_str(&fzf_opts(scheme));
+    body.push_str("\n# Running shells pick up a switch at their next prompt\n");
+    body.push_str(&reload_hook(colors_file));
+    let stamp = stamp(&body);
+    Ok(format!(
+        "{STAMP_PREFIX}{stamp}\n{body}_lmtt_stamp={stamp}\n"
+    ))
+}
+
+#[async_trait]
+impl ThemeModule for BashModule {
+    fn name(&self) -> &'static str {
+        "bash"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "bash"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = shell_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("colors.bash");
+        let content = colors_bash(scheme, &colors_file)?;
+        lmtt_core::fsutil::write_atomic(&colors_file, content).await?;
+
+        tracing::info!("[Bash] Updated colors at {}", colors_file.display());
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let Some(bashrc) = dirs::home_dir()
+            .map(|home| home.join(".bashrc"))
+            .filter(|path| path.exists())
+        else {
+            return Ok(vec![]);
+        };
+
+        let content = tokio::fs::read_to_string(&bashrc).await?;
+        let already_included = crate::is_included(&content, INCLUDE_LINE);
+
+        Ok(vec![ConfigFileInfo {
+            path: bashrc,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Source lmtt colors in .bashrc".to_string(),
+            already_included,
+        }])
+    }
+
+    /// After the user's own FZF_DEFAULT_OPTS, which it extends, and after
+    /// any plain `PROMPT_COMMAND=` that would drop the hook.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_is_added_to_prompt_command_once() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Light);
+        scheme.set("on_surface".into(), "#191c20".into());
+        let colors_file = Path::new("/home/u/.config/lmtt/shell/colors.bash");
+        let content = colors_bash(&scheme, colors_file).unwrap();
+        assert!(content.contains("export LMTT_ON_SURFACE='#191c20'\n"));
+        assert!(content.contains("export FZF_DEFAULT_OPTS=\"${LMTT_FZF_USER_OPTS:+$LMTT_FZF_USER_OPTS }--color=fg:#191c20,"));
+        assert!(content.contains(
+            "[[ \"${PROMPT_COMMAND[*]}\" == *_lmtt_precmd* ]] ||\n    PROMPT_COMMAND=\"_lmtt_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}\"\n"
+        ));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::{Path, PathBuf};
+
+crate::register_module!(BatModule);
+
+/// bat's syntax theme, also used by delta, which reads bat's theme cache.
+pub struct BatModule;
+
+impl Default for BatModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Debian and Ubuntu ship bat as `batcat`.
+const BINARIES: [&str; 2] = ["bat", "batcat"];
+
+const BAT_INCLUDE_LINE: &str = "--theme=lmtt";
+
+/// The line setup shows for delta; the setting itself goes in with
+/// `git config`, into the `[delta]` section.
+const DELTA_INCLUDE_LINE: &str = "syntax-theme = lmtt";
+
+/// Where setup keeps the delta syntax theme it replaced, for cleanup to put
+/// back. Its own section, since delta reads every `delta.*` key.
+const PREVIOUS_DELTA_THEME: &str = "lmtt.delta-syntax-theme";
+
+fn bat_binary() -> Option<&'static str> {
+    BINARIES.into_iter().find(|bin| which::which(bin).is_ok())
+}
+
+/// Ask bat for one of its paths (`--config-dir`, `--config-file`,
+/// `--cache-dir`), which accounts for $BAT_CONFIG_DIR, $BAT_CONFIG_PATH and
+/// $BAT_CACHE_PATH.
+async fn bat_path(bin: &str, flag: &str) -> Option<PathBuf> {
+    let output = tokio::process::Command::new(bin)
+        .arg(flag)
+        .output()
+        .await
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The global git config `git config --global` would write to: ~/.gitconfig,
+/// else the XDG one. None when neither exists.
+fn git_config_path() -> Option<PathBuf> {
+    let home = dirs::home_dir()?.join(".gitconfig");
+    if home.exists() {
+        return Some(home);
+    }
+    let xdg = dirs::config_dir()?.join("git").join("config");
+    xdg.exists().then_some(xdg)
+}
+
+async fn cache_is_stale(bin: &str, theme_file: &Path) -> bool {
+    let Some(cache_dir) = bat_path(bin, "--cache-dir").await else {
+        return true;
+    };
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (
+        modified(&cache_dir.join("themes.bin")),
+        modified(theme_file),
+    ) {
+        (Some(cache), Some(theme)) => cache < theme,
+        _ => true,
+    }
+}
+
+async fn git_config_get(git_config: &Path, key: &str) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .args(["config", "--file"])
+        .arg(git_config)
+        .args(["--get", key])
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn delta_syntax_theme(git_config: &Path) -> Option<String> {
+    git_config_get(git_config, "delta.syntax-theme").await
+}
+
+async fn git_config(git_config: &Path, args: &[&str]) -> Result<()> {
+    let output = tokio::process::Command::new("git")
+        .args(["config", "--file"])
+        .arg(git_config)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| lmtt_core::Error::Module(format!("git failed to run: {}", e)))?;
+    if !output.status.success() {
+        return Err(lmtt_core::Error::Module(format!(
+            "git config {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// One `<dict>` of the theme's settings array: a scope selector and its
+/// foreground, background and font style, each optional.
+struct Rule<'a> {
+    name: &'a str,
+    scope: &'a str,
+    foreground: Option<&'a str>,
+    background: Option<&'a str>,
+    font_style: Option<&'a str>,
+}
+
+fn fg<'a>(name: &'a str, scope: &'a str, color: &'a str) -> Rule<'a> {
+    Rule {
+        name,
+        scope,
+        foreground: Some(color),
+        background: None,
+        font_style: None,
+    }
+}
+
+fn styled<'a>(rule: Rule<'a>, font_style: &'a str) -> Rule<'a> {
+    Rule {
+        font_style: Some(font_style),
+        ..rule
+    }
+}
+
+fn plist_dict(entries: &[(&str, &str)], indent: &str) -> String {
+    let mut out = format!("{indent}<dict>\n");
+    for (key, value) in entries {
+        out.push_str(&format!(
+            "{indent}    <key>{key}</key>\n{indent}    <string>{value}</string>\n"
+        ));
+    }
+    out.push_str(&format!("{indent}</dict>\n"));
+    out
+}
+
+/// A TextMate theme: editor colors plus the same syntax roles as the other
+/// generated editor themes (keywords primary, functions tertiary, types
+/// secondary, strings and constants from the terminal palette).
+fn tm_theme(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let diag = terminal::diagnostics(scheme);
+
+    let surface = c("surface");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let primary = c("primary");
+    let secondary = c("secondary");
+    let tertiary = c("tertiary");
+    let outline = c("outline");
+    let outline_variant = c("outline_variant");
+    let container_low = c("surface_container_low");
+    let container_high = c("surface_container_high");
+    let error = c("error");
+    let green = &term.ansi[2];
+    let yellow = &term.ansi[3];
+    let cyan = &term.ansi[6];
+
+    let globals = [
+        ("background", surface.as_str()),
+        ("foreground", on_surface.as_str()),
+        ("caret", primary.as_str()),
+        ("lineHighlight", container_low.as_str()),
+        ("selection", container_high.as_str()),
+        ("invisibles", outline_variant.as_str()),
+        ("gutter", surface.as_str()),
+        ("gutterForeground", outline.as_str()),
+        ("findHighlight", container_high.as_str()),
+    ];
+
+    let rules = [
+        styled(
+            fg(
+                "Comment",
+                "comment, punctuation.definition.comment",
+                &outline,
+            ),
+            "italic",
+        ),
+        fg("Keyword", "keyword, storage.modifier", &primary),
+        styled(fg("Control", "keyword.control", &primary), "bold"),
+        fg("Operator", "keyword.operator", &on_surface_variant),
+        fg(
+            "Function",
+            "entity.name.function, support.function, meta.function-call",
+            &tertiary,
+        ),
+        fg(
+            "Type",
+            "entity.name.type, entity.name.class, support.type, support.class, storage.type",
+            &secondary,
+        ),
+        fg(
+            "Namespace",
+            "entity.name.namespace, entity.name.module",
+            &secondary,
+        ),
+        fg("String", "string", green),
+        fg("Escape", "constant.character.escape, string.regexp", cyan),
+        fg(
+            "Constant",
+            "constant, constant.numeric, constant.language, support.constant",
+            cyan,
+        ),
+        fg("Variable", "variable", &on_surface),
+        fg("Parameter", "variable.parameter", &on_surface_variant),
+        fg("Builtin variable", "variable.language", &secondary),
+        fg("Punctuation", "punctuation", &on_surface_variant),
+        fg("Tag", "entity.name.tag", &primary),
+        fg("Attribute", "entity.other.attribute-name", &tertiary),
+        styled(fg("Heading", "markup.heading", &primary), "bold"),
+        styled(fg("Bold", "markup.bold", &on_surface), "bold"),
+        styled(fg("Italic", "markup.italic", &on_surface), "italic"),
+        styled(fg("Link", "markup.underline.link", &tertiary), "underline"),
+        fg("Quote", "markup.quote", &on_surface_variant),
+        fg("Raw", "markup.raw", green),
+        fg("Inserted", "markup.inserted", green),
+        fg("Deleted", "markup.deleted", &error),
+        fg("Changed", "markup.changed", yellow),
+        fg("Diff header", "meta.diff.header, meta.diff.range", &primary),
+        fg("Warning", "markup.warning", &diag.warning),
+        Rule {
+            name: "Invalid",
+            scope: "invalid",
+            foreground: Some(&surface),
+            background: Some(&error),
+            font_style: None,
+        },
+    ];
+
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    content.push_str("<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n");
+    content.push_str(&format!(
+        "<!-- bat theme generated by lmtt, mode: {} -->\n",
+        scheme.mode
+    ));
+    content.push_str("<plist version=\"1.0\">\n<dict>\n");
+    content.push_str("    <key>name</key>\n    <string>lmtt</string>\n");
+    content.push_str("    <key>settings</key>\n    <array>\n");
+    content.push_str("        <dict>\n            <key>settings</key>\n");
+    content.push_str(&plist_dict(&globals, "            "));
+    content.push_str("        </dict>\n");
+    for rule in &rules {
+        let mut settings = Vec::new();
+        if let Some(foreground) = rule.foreground {
+            settings.push(("foreground", foreground));
+        }
+        if let Some(background) = rule.background {
+            settings.push(("background", background));
+        }
+        if let Some(font_style) = rule.font_style {
+            settings.push(("fontStyle", font_style));
+        }
+        content.push_str("        <dict>\n");
+        content.push_str(&format!(
+            "            <key>name</key>\n            <string>{}</string>\n",
+            rule.name
+        ));
+        content.push_str(&format!(
+            "            <key>scope</key>\n            <string>{}</string>\n",
+            rule.scope
+        ));
+        content.push_str("            <key>settings</key>\n");
+        content.push_str(&plist_dict(&settings, "            "));
+        content.push_str("        </dict>\n");
+    }
+    content.push_str("    </array>\n</dict>\n</plist>\n");
+    content
+}
+
+#[async_trait]
+impl ThemeModule for BatModule {
+    fn name(&self) -> &'static str {
+        "bat"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "bat"
+    }
+
+    fn is_installed(&self) -> bool {
+        bat_binary().is_some()
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let bin =
+            bat_binary().ok_or_else(|| lmtt_core::Error::Module("bat is not installed".into()))?;
+        let config_dir = match bat_path(bin, "--config-dir").await {
+            Some(dir) => dir,
+            None => dirs::config_dir()
+                .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+                .join("bat"),
+        };
+        let themes_dir = config_dir.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+        let theme_file = themes_dir.join("lmtt.tmTheme");
+        let theme = tm_theme(scheme);
+        let existing = tokio::fs::read_to_string(&theme_file)
+            .await
+            .unwrap_or_default();
+        if existing != theme {
+            lmtt_core::fsutil::write_atomic(&theme_file, theme).await?;
+        }
+
+        // bat and delta only load themes from the compiled cache. Rebuilding
+        // it takes about a second, so only when the theme is newer than the
+        // cache: changed now, or left unbuilt by a failed rebuild.
+        if !cache_is_stale(bin, &theme_file).await {
+            tracing::info!("[Bat] Theme at {} unchanged", theme_file.display());
+            return Ok(());
+        }
+        let output = tokio::process::Command::new(bin)
+            .args(["cache", "--build"])
+            .output()
+            .await
+            .map_err(|e| lmtt_core::Error::Module(format!("{} failed to run: {}", bin, e)))?;
+        if !output.status.success() {
+            return Err(lmtt_core::Error::Module(format!(
+                "{} cache --build failed: {}",
+                bin,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        tracing::info!(
+            "[Bat] Updated theme at {} and rebuilt the cache",
+            theme_file.display()
+        );
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let mut files = Vec::new();
+
+        if let Some(bin) = bat_binary() {
+            if let Some(config) = bat_path(bin, "--config-file").await {
+                if config.exists() {
+                    let content = tokio::fs::read_to_string(&config).await?;
+                    let already_included = crate::is_included(&content, BAT_INCLUDE_LINE);
+                    files.push(ConfigFileInfo {
+                        path: config,
+                        include_line: BAT_INCLUDE_LINE.to_string(),
+                        description: "Use the LMTT theme in bat config".to_string(),
+                        already_included,
+                    });
+                }
+            }
+        }
+
+        if which::which("delta").is_ok() {
+            if let Some(git_config) = git_config_path() {
+                let already_included =
+                    delta_syntax_theme(&git_config).await.as_deref() == Some("lmtt");
+                files.push(ConfigFileInfo {
+                    path: git_config,
+                    include_line: DELTA_INCLUDE_LINE.to_string(),
+                    description: "Use the LMTT theme for delta in [delta] of git config"
+                        .to_string(),
+                    already_included,
+                });
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// bat takes the last `--theme` it is given, so the line goes after the
+    /// user's own. delta's setting belongs in the `[delta]` section, which
+    /// `git config` finds or creates; a syntax theme the user had is saved
+    /// under `[lmtt]` first.
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        if config_file.include_line == DELTA_INCLUDE_LINE {
+            let path = &config_file.path;
+            if let Some(previous) = delta_syntax_theme(path).await.filter(|t| t != "lmtt") {
+                git_config(path, &[PREVIOUS_DELTA_THEME, &previous]).await?;
+            }
+            return git_config(path, &["delta.syntax-theme", "lmtt"]).await;
+        }
+        crate::inject_managed_block(&config_file.path, &config_file.include_line, &[], true).await
+    }
+
+    /// delta's syntax theme goes back to the one setup saved, or is unset,
+    /// but only while it is still lmtt's. A theme the user set since stays.
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        if config_file.include_line == DELTA_INCLUDE_LINE {
+            let path = &config_file.path;
+            let previous = git_config_get(path, PREVIOUS_DELTA_THEME).await;
+            if previous.is_some() {
+                git_config(path, &["--remove-section", "lmtt"]).await?;
+            }
+            if delta_syntax_theme(path).await.as_deref() != Some("lmtt") {
+                return Ok(false);
+            }
+            match previous {
+                Some(previous) => git_config(path, &["delta.syntax-theme", &previous]).await?,
+                None => git_config(path, &["--unset", "delta.syntax-theme"]).await?,
+            }
+            return Ok(true);
+        }
+        crate::remove_managed_blocks(&config_file.path, &config_file.include_line, &[]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_is_a_plist_with_global_and_scope_settings() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Light);
+        scheme.set("surface".into(), "#f8f9ff".into());
+        scheme.set("primary".into(), "#3a5f8a".into());
+        let theme = tm_theme(&scheme);
+        assert!(theme.starts_with("<?xml"));
+        assert!(theme.contains("<key>background</key>\n                <string>#f8f9ff</string>"));
+        assert!(theme.contains(
+            "<string>keyword, storage.modifier</string>\n            <key>settings</key>\n            <dict>\n                <key>foreground</key>\n                <string>#3a5f8a</string>"
+        ));
+        assert_eq!(
+            theme.matches("<dict>").count(),
+            theme.matches("</dict>").count()
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(BtopModule);
+
+pub struct BtopModule;
+
+impl Default for BtopModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BtopModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const INCLUDE_LINE: &str = "color_theme = \"lmtt\"";
+
+/// Next to btop.conf, the `color_theme` setup replaced. btop rewrites
+/// btop.conf on exit, so it can't be kept there as a comment.
+const PREVIOUS_THEME_FILE: &str = "lmtt-previous-theme";
+
+/// First release that reloads on SIGUSR2. Older ones leave the signal at its
+/// default action, which terminates btop.
+const SIGUSR2_RELOAD_VERSION: (u32, u32) = (1, 3);
+
+fn btop_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("btop"))
+}
+
+/// Parse `btop --version` ("btop version: 1.3.2", possibly followed by
+/// build details) into (major, minor).
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("btop version:"))?;
+    let mut parts = version.trim().split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+async fn supports_sigusr2_reload() -> bool {
+    let Ok(output) = tokio::process::Command::new("btop")
+        .arg("--version")
+        .output()
+        .await
+    else {
+        return false;
+    };
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .is_some_and(|version| version >= SIGUSR2_RELOAD_VERSION)
+}
+
+/// A btop theme: UI colors from the scheme, and each graph's gradient from
+/// a calm start through the accent to a warning-hued end.
+fn theme_file(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let surface = c("surface");
+    let primary = c("primary");
+    let secondary = c("secondary");
+    let tertiary = c("tertiary");
+    let error = c("error");
+    let green = &term.ansi[2];
+    let yellow = &term.ansi[3];
+    let cyan = &term.ansi[6];
+    // Gradient starts fade toward the background so empty graphs stay quiet
+    let soft = |color: &str| {
+        lmtt_core::colors::mix_oklab(color, &surface, 0.5).unwrap_or_else(|_| color.to_string())
+    };
+
+    let theme = [
+        ("main_bg", surface.clone()),
+        ("main_fg", c("on_surface")),
+        ("title", c("on_surface")),
+        ("hi_fg", primary.clone()),
+        ("selected_bg", c("primary_container")),
+        ("selected_fg", c("on_primary_container")),
+        ("inactive_fg", c("outline")),
+        ("graph_text", c("on_surface_variant")),
+        ("meter_bg", c("surface_container_highest")),
+        ("proc_misc", tertiary.clone()),
+        ("cpu_box", primary.clone()),
+        ("mem_box", secondary.clone()),
+        ("net_box", tertiary.clone()),
+        ("proc_box", primary.clone()),
+        ("div_line", c("outline_variant")),
+    ];
+    let gradients: [(&str, &str, &str); 9] = [
+        ("temp", green, &error),
+        ("cpu", &primary, &error),
+        ("free", green, cyan),
+        ("cached", &secondary, &primary),
+        ("available", &tertiary, green),
+        ("used", &primary, &error),
+        ("download", &secondary, yellow),
+        ("upload", &tertiary, yellow),
+        ("process", &primary, &error),
+    ];
+
+    let mut content = String::new();
+    content.push_str("# btop theme generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    for (key, color) in theme {
+        content.push_str(&format!("theme[{}]=\"{}\"\n", key, color));
+    }
+    for (name, mid, end) in gradients {
+        content.push_str(&format!("theme[{}_start]=\"{}\"\n", name, soft(mid)));
+        content.push_str(&format!("theme[{}_mid]=\"{}\"\n", name, mid));
+        content.push_str(&format!("theme[{}_end]=\"{}\"\n", name, end));
+    }
+    content
+}
+
+/// btop.conf with `color_theme` pointing at `theme`. btop rewrites the whole
+/// file on exit, dropping comments, so the key is replaced in place rather
+/// than overridden from a managed block.
+fn set_color_theme(content: &str, theme: &str) -> String {
+    let line = format!("color_theme = \"{}\"", theme);
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|l| {
+            if l.trim_start().starts_with("color_theme") && l.contains('=') && !found {
+                found = true;
+                line.clone()
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(line);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// The value of the first `color_theme` line, unquoted.
+fn color_theme(content: &str) -> Option<String> {
+    content
+        .lines()
+        .filter(|l| l.trim_start().starts_with("color_theme"))
+        .find_map(|l| l.split_once('='))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+}
+
+fn color_theme_is_lmtt(content: &str) -> bool {
+    content.lines().any(|l| l.trim() == INCLUDE_LINE)
+}
+
+#[async_trait]
+impl ThemeModule for BtopModule {
+    fn name(&self) -> &'static str {
+        "btop"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "btop"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let themes_dir = btop_dir()?.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+        let theme = themes_dir.join("lmtt.theme");
+        lmtt_core::fsutil::write_atomic(&theme, theme_file(scheme)).await?;
+        tracing::info!("[Btop] Updated theme at {}", theme.display());
+
+        if !crate::process_running("btop").await {
+            return Ok(());
+        }
+        if !supports_sigusr2_reload().await {
+            tracing::info!(
+                "[Btop] Version predates SIGUSR2 reload; restart btop for the new theme"
+            );
+            return Ok(());
+        }
+        // SIGUSR2 makes btop reload its config, and with it the theme.
+        let _ = tokio::process::Command::new("pkill")
+            .args(["-USR2", "-x", "btop"])
+            .output()
+            .await;
+        tracing::info!("[Btop] Reloaded running btop via SIGUSR2");
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config = btop_dir()?.join("btop.conf");
+        if !config.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = color_theme_is_lmtt(&content);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Use the LMTT theme in btop.conf".to_string(),
+            already_included,
+        }])
+    }
+
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        if let Some(previous) = color_theme(&content).filter(|theme| theme != "lmtt") {
+            let saved = path.with_file_name(PREVIOUS_THEME_FILE);
+            lmtt_core::fsutil::write_atomic(&saved, format!("{}\n", previous)).await?;
+        }
+        let new_content = set_color_theme(&content, "lmtt");
+        if new_content != content {
+            lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        }
+        Ok(())
+    }
+
+    /// Back to the theme setup replaced (btop's default if none was saved),
+    /// unless the user picked another since.
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let saved = path.with_file_name(PREVIOUS_THEME_FILE);
+        let previous = tokio::fs::read_to_string(&saved)
+            .await
+            .ok()
+            .map(|theme| theme.trim().to_string())
+            .filter(|theme| !theme.is_empty());
+        if saved.exists() {
+            tokio::fs::remove_file(&saved).await?;
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        if !color_theme_is_lmtt(&content) {
+            return Ok(false);
+        }
+        let theme = previous.as_deref().unwrap_or("Default");
+        lmtt_core::fsutil::write_atomic(path, set_color_theme(&content, theme)).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(parse_version("btop version: 1.3.2\n"), Some((1, 3)));
+        assert_eq!(
+            parse_version("btop version: 1.4.0\nCompiled with: g++ (14.2.1)\n"),
+            Some((1, 4))
+        );
+        assert!(parse_version("btop version: 1.2.13").unwrap() < SIGUSR2_RELOAD_VERSION);
+        assert_eq!(parse_version("btop: command not found"), None);
+    }
+
+    #[test]
+    fn color_theme_is_replaced_in_place() {
+        let conf = "#? Config file for btop\n\n#* Name of a btop formatted \".theme\" file\ncolor_theme = \"Default\"\n\ntheme_background = True\n";
+        let set = set_color_theme(conf, "lmtt");
+        assert_eq!(set, conf.replace("\"Default\"", "\"lmtt\""));
+        assert!(color_theme_is_lmtt(&set));
+        assert_eq!(set_color_theme(&set, "Default"), conf);
+        assert!(set_color_theme("", "lmtt").ends_with("color_theme = \"lmtt\"\n"));
+        assert_eq!(color_theme(conf).as_deref(), Some("Default"));
+        assert_eq!(color_theme("theme_background = True\n"), None);
+    }
+
+    #[tokio::test]
+    async fn cleanup_restores_the_theme_setup_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("btop.conf");
+        std::fs::write(&path, "color_theme = \"nord\"\n").unwrap();
+        let info = ConfigFileInfo {
+            path: path.clone(),
+            include_line: INCLUDE_LINE.to_string(),
+            description: String::new(),
+            already_included: false,
+        };
+        BtopModule.inject_config(&info).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "color_theme = \"lmtt\"\n"
+        );
+        assert!(BtopModule.remove_config(&info).await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "color_theme = \"nord\"\n"
+        );
+        assert!(!dir.path().join(PREVIOUS_THEME_FILE).exists());
+    }
+}
@@ -62,6 +62,9 @@ pub struct ReloadConfig {
     pub command: String,
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// Only reload when a process with exactly this name is running.
+    #[serde(default)]
+    pub process: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -207,6 +210,10 @@ impl ThemeModule for CustomModule {
         self.definition.priority
     }
 
+    fn is_custom(&self) -> bool {
+        true
+    }
+
     fn max_apply_secs(&self) -> Option<u64> {
         // Report this module's own configured timeout(s) so the registry
         // watchdog doesn't cap a legitimately long script/reload.
@@ -341,6 +348,17 @@ impl CustomModule {
     }
 
     async fn run_reload(&self, reload: &ReloadConfig) -> Result<()> {
+        if let Some(process) = &reload.process {
+            if !crate::process_running(process).await {
+                tracing::debug!(
+                    "[{}] {} not running, skipping reload",
+                    self.definition.name,
+                    process
+                );
+                return Ok(());
+            }
+        }
+
         // kill_on_drop: when the timeout fires and this future is dropped, the
         // child sh (and its process group) is killed instead of orphaned.
         let mut cmd = tokio::process::Command::new("sh");
@@ -529,6 +547,7 @@ mod tests {
                 reload: ReloadConfig {
                     command: "true".to_string(),
                     timeout: 1000,
+                    process: None,
                 },
             },
         })
@@ -559,6 +578,19 @@ mod tests {
         assert_eq!(rendered, "primary=#123456 mode=dark\n");
     }
 
+    #[tokio::test]
+    async fn reload_skipped_when_process_not_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("reloaded");
+        let reload = ReloadConfig {
+            command: format!("touch '{}'", marker.display()),
+            timeout: 1000,
+            process: Some("lmtt-no-such-process".to_string()),
+        };
+        test_module().run_reload(&reload).await.unwrap();
+        assert!(!marker.exists());
+    }
+
     #[tokio::test]
     async fn template_rejects_both_content_and_path() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,395 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(DircolorsModule);
+
+/// `LS_COLORS` for ls, fd, tree and eza, plus an eza theme.
+pub struct DircolorsModule;
+
+impl Default for DircolorsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DircolorsModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Sourced by bash and zsh; plain `export`, so either shell reads it.
+const SHELL_INCLUDE_LINE: &str =
+    "[ -r ~/.config/lmtt/shell/ls-colors.sh ] && . ~/.config/lmtt/shell/ls-colors.sh";
+
+/// First line of an eza theme lmtt wrote, so a user's own theme.yml is
+/// never overwritten.
+const EZA_HEADER: &str = "# eza theme generated by lmtt";
+
+const ARCHIVES: &[&str] = &[
+    "tar", "tgz", "gz", "xz", "zst", "bz2", "lz4", "zip", "7z", "rar", "deb", "rpm",
+];
+const IMAGES: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "bmp", "svg", "ico", "tif", "tiff",
+];
+const MEDIA: &[&str] = &[
+    "mp3", "flac", "ogg", "opus", "wav", "m4a", "mp4", "mkv", "webm", "mov", "avi",
+];
+const DOCUMENTS: &[&str] = &["md", "txt", "pdf", "org", "rst", "epub"];
+const BACKUPS: &[&str] = &["bak", "swp", "tmp", "orig", "old", "log"];
+
+/// SGR truecolor foreground, e.g. `38;2;156;202;255`.
+fn sgr_fg(hex: &str) -> String {
+    let (r, g, b) = lmtt_core::colors::hex_to_rgb(hex).unwrap_or((0, 0, 0));
+    format!("38;2;{};{};{}", r, g, b)
+}
+
+fn sgr_bg(hex: &str) -> String {
+    let (r, g, b) = lmtt_core::colors::hex_to_rgb(hex).unwrap_or((0, 0, 0));
+    format!("48;2;{};{};{}", r, g, b)
+}
+
+/// An `LS_COLORS` value. File kinds take the terminal palette's
+/// conventional hues (tuned per mode to read on the terminal background),
+/// so a green executable is green in both modes; dirs and links follow the
+/// scheme accents.
+fn ls_colors(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let [_, red, green, yellow, blue, magenta, cyan, _] = &term.ansi;
+    let on_container = |fg: &str, bg: &str| format!("{};{}", sgr_fg(&c(fg)), sgr_bg(&c(bg)));
+
+    let mut entries: Vec<(String, String)> = vec![
+        ("rs".into(), "0".into()),
+        ("di".into(), format!("1;{}", sgr_fg(&c("primary")))),
+        ("ln".into(), sgr_fg(cyan)),
+        ("or".into(), format!("1;{}", sgr_fg(&c("error")))),
+        ("mi".into(), format!("1;{}", sgr_fg(&c("error")))),
+        ("ex".into(), format!("1;{}", sgr_fg(green))),
+        ("pi".into(), sgr_fg(yellow)),
+        ("so".into(), format!("1;{}", sgr_fg(magenta))),
+        ("do".into(), format!("1;{}", sgr_fg(magenta))),
+        ("bd".into(), format!("1;{}", sgr_fg(yellow))),
+        ("cd".into(), format!("1;{}", sgr_fg(yellow))),
+        ("su".into(), on_container("on_error", "error")),
+        ("sg".into(), on_container("on_error", "error")),
+        ("ca".into(), on_container("on_error", "error")),
+        (
+            "tw".into(),
+            on_container("on_tertiary_container", "tertiary_container"),
+        ),
+        (
+            "ow".into(),
+            on_container("on_primary_container", "primary_container"),
+        ),
+        (
+            "st".into(),
+            on_container("on_secondary_container", "secondary_container"),
+        ),
+    ];
+
+    for (extensions, sgr) in [
+        (ARCHIVES, format!("1;{}", sgr_fg(red))),
+        (IMAGES, sgr_fg(magenta)),
+        (MEDIA, sgr_fg(blue)),
+        (DOCUMENTS, sgr_fg(&c("on_surface_variant"))),
+        (BACKUPS, sgr_fg(&c("outline"))),
+    ] {
+        for extension in extensions {
+            entries.push((format!("*.{}", extension), sgr.clone()));
+        }
+    }
+    entries.push(("*~".into(), sgr_fg(&c("outline"))));
+
+    entries
+        .iter()
+        .map(|(key, sgr)| format!("{}={}", key, sgr))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn ls_colors_sh(scheme: &ColorScheme, value: &str) -> String {
+    format!(
+        "# LS_COLORS generated by lmtt\n# Mode: {}\nexport LS_COLORS='{}'\n",
+        scheme.mode, value
+    )
+}
+
+/// eza's theme.yml: the same file kinds as `LS_COLORS`, plus the columns
+/// of `eza -l` that `LS_COLORS` can't reach.
+fn eza_theme(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let [_, red, green, yellow, blue, magenta, cyan, _] = &term.ansi;
+    let fg = |color: &str| format!("{{foreground: \"{}\"}}", color);
+    let bold = |color: &str| format!("{{foreground: \"{}\", is_bold: true}}", color);
+
+    let primary = c("primary");
+    let secondary = c("secondary");
+    let tertiary = c("tertiary");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let outline = c("outline");
+    let error = c("error");
+
+    let sections: Vec<(&str, Vec<(&str, String)>)> = vec![
+        (
+            "filekinds",
+            vec![
+                ("normal", fg(&on_surface)),
+                ("directory", bold(&primary)),
+                ("symlink", fg(cyan)),
+                ("pipe", fg(yellow)),
+                ("block_device", bold(yellow)),
+                ("char_device", bold(yellow)),
+                ("socket", bold(magenta)),
+                ("special", fg(magenta)),
+                ("executable", bold(green)),
+                ("mount_point", bold(&tertiary)),
+            ],
+        ),
+        (
+            "perms",
+            vec![
+                ("user_read", fg(yellow)),
+                ("user_write", fg(red)),
+                ("user_execute_file", bold(green)),
+                ("user_execute_other", fg(green)),
+                ("group_read", fg(yellow)),
+                ("group_write", fg(red)),
+                ("group_execute", fg(green)),
+                ("other_read", fg(yellow)),
+                ("other_write", fg(red)),
+                ("other_execute", fg(green)),
+                ("special_user_file", fg(magenta)),
+                ("special_other", fg(magenta)),
+                ("attribute", fg(&outline)),
+            ],
+        ),
+        (
+            "size",
+            vec![
+                ("major", fg(&secondary)),
+                ("minor", fg(&secondary)),
+                ("number_byte", fg(&on_surface_variant)),
+                ("number_kilo", fg(&on_surface)),
+                ("number_mega", fg(blue)),
+                ("number_giga", bold(&tertiary)),
+                ("number_huge", bold(&error)),
+                ("unit_byte", fg(&outline)),
+                ("unit_kilo", fg(&outline)),
+                ("unit_mega", fg(&outline)),
+                ("unit_giga", fg(&outline)),
+                ("unit_huge", fg(&outline)),
+            ],
+        ),
+        (
+            "users",
+            vec![
+                ("user_you", fg(&secondary)),
+                ("user_root", bold(&error)),
+                ("user_other", fg(&on_surface_variant)),
+                ("group_yours", fg(&secondary)),
+                ("group_other", fg(&on_surface_variant)),
+                ("group_root", bold(&error)),
+            ],
+        ),
+        (
+            "links",
+            vec![("normal", fg(cyan)), ("multi_link_file", bold(cyan))],
+        ),
+        (
+            "git",
+            vec![
+                ("new", fg(green)),
+                ("modified", fg(yellow)),
+                ("deleted", fg(red)),
+                ("renamed", fg(blue)),
+                ("typechange", fg(magenta)),
+                ("ignored", fg(&outline)),
+                ("conflicted", bold(red)),
+            ],
+        ),
+        (
+            "git_repo",
+            vec![
+                ("branch_main", fg(green)),
+                ("branch_other", fg(yellow)),
+                ("git_clean", fg(green)),
+                ("git_dirty", fg(yellow)),
+            ],
+        ),
+    ];
+
+    let mut content = String::new();
+    content.push_str(EZA_HEADER);
+    content.push_str(&format!("\n# Mode: {}\n", scheme.mode));
+    content.push_str("colourful: true\n");
+    for (section, entries) in sections {
+        content.push_str(&format!("{}:\n", section));
+        for (key, style) in entries {
+            content.push_str(&format!("  {}: {}\n", key, style));
+        }
+    }
+    for (key, style) in [
+        ("date", fg(&on_surface_variant)),
+        ("inode", fg(&outline)),
+        ("blocks", fg(&outline)),
+        ("header", bold(&on_surface)),
+        ("octal", fg(&tertiary)),
+        ("punctuation", fg(&outline)),
+        ("control_char", fg(red)),
+        ("broken_symlink", bold(&error)),
+        ("broken_path_overlay", fg(&error)),
+    ] {
+        content.push_str(&format!("{}: {}\n", key, style));
+    }
+    content
+}
+
+/// eza reads theme.yml only. Write it unless the user has their own, in
+/// which case the theme goes next to it for them to copy from.
+async fn write_eza_theme(scheme: &ColorScheme) -> Result<()> {
+    let dir = match std::env::var_os("EZA_CONFIG_DIR").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::config_dir()
+            .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+            .join("eza"),
+    };
+    let theme_file = dir.join("theme.yml");
+    let ours = match tokio::fs::read_to_string(&theme_file).await {
+        Ok(content) => content.starts_with(EZA_HEADER),
+        Err(_) => true,
+    };
+    let target = if ours {
+        theme_file
+    } else {
+        dir.join("lmtt-theme.yml")
+    };
+    tokio::fs::create_dir_all(&dir).await?;
+    lmtt_core::fsutil::write_atomic(&target, eza_theme(scheme)).await?;
+    if ours {
+        tracing::info!("[Dircolors] Updated eza theme at {}", target.display());
+    } else {
+        tracing::info!(
+            "[Dircolors] theme.yml is your own; wrote eza colors to {}",
+            target.display()
+        );
+    }
+    Ok(())
+}
+
+/// fish gets `LS_COLORS` as an exported universal variable, which reaches
+/// running shells. The conf.d file drops a copy inherited from the session
+/// environment: that global would shadow the universal one.
+async fn apply_fish(value: &str) -> Result<()> {
+    let conf_d = dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("fish")
+        .join("conf.d");
+    tokio::fs::create_dir_all(&conf_d).await?;
+    lmtt_core::fsutil::write_atomic(
+        &conf_d.join("lmtt-ls-colors.fish"),
+        "# Generated by lmtt: LS_COLORS is a universal variable, updated on every\n\
+         # switch. Drop the copy inherited from the environment, which shadows it.\n\
+         if set -q -U LS_COLORS\n    set -e -g LS_COLORS\nend\n"
+            .to_string(),
+    )
+    .await?;
+
+    // NOT --no-config: universal variables need fish's normal startup
+    let output = tokio::process::Command::new("fish")
+        .args(["-c", &format!("set -Ux LS_COLORS '{}'", value)])
+        .output()
+        .await
+        .map_err(|e| lmtt_core::Error::Module(format!("fish failed to run: {}", e)))?;
+    if !output.status.success() {
+        return Err(lmtt_core::Error::Module(format!(
+            "fish LS_COLORS update failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ThemeModule for DircolorsModule {
+    fn name(&self) -> &'static str {
+        "dircolors"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "ls"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let value = ls_colors(scheme);
+
+        let dir = crate::shell::shell_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let sh_file = dir.join("ls-colors.sh");
+        lmtt_core::fsutil::write_atomic(&sh_file, ls_colors_sh(scheme, &value)).await?;
+        tracing::info!("[Dircolors] Updated LS_COLORS at {}", sh_file.display());
+
+        if which::which("fish").is_ok() {
+            apply_fish(&value).await?;
+            tracing::info!("[Dircolors] Updated LS_COLORS for fish (live shells included)");
+        }
+
+        if which::which("eza").is_ok() {
+            write_eza_theme(scheme).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let Some(home) = dirs::home_dir() else {
+            return Ok(vec![]);
+        };
+        let mut files = Vec::new();
+        for rc in [".bashrc", ".zshrc"] {
+            let path = home.join(rc);
+            if !path.exists() {
+                continue;
+            }
+            let content = tokio::fs::read_to_string(&path).await?;
+            let already_included = crate::is_included(&content, SHELL_INCLUDE_LINE);
+            files.push(ConfigFileInfo {
+                path,
+                include_line: SHELL_INCLUDE_LINE.to_string(),
+                description: format!("Source lmtt LS_COLORS in {}", rc),
+                already_included,
+            });
+        }
+        Ok(files)
+    }
+
+    /// After the user's own `eval "$(dircolors)"`, which would otherwise
+    /// reset LS_COLORS.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ls_colors_entries_are_well_formed() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#9ccaff".into());
+        let value = ls_colors(&scheme);
+        assert!(value.contains(":di=1;38;2;156;202;255:"));
+        assert!(value.contains(":*.tar=1;38;2;"));
+        for entry in value.split(':') {
+            let (key, sgr) = entry.split_once('=').unwrap();
+            assert!(!key.is_empty());
+            assert!(sgr.split(';').all(|n| n.parse::<u8>().is_ok()), "{}", entry);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(DunstModule);
+
+pub struct DunstModule;
+
+impl Default for DunstModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DunstModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// First release that reads `dunstrc.d/*.conf` drop-ins.
+const DROP_IN_VERSION: (u32, u32) = (1, 9);
+
+/// First release with `dunstctl reload`.
+const RELOAD_VERSION: (u32, u32) = (1, 11);
+
+/// systemd user unit dunst ships for D-Bus activation.
+const SYSTEMD_UNIT: &str = "dunst.service";
+
+fn drop_in_path() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("dunst")
+        .join("dunstrc.d")
+        .join("90-lmtt.conf"))
+}
+
+/// Colors for the three urgency levels. Drop-ins are read after dunstrc in
+/// name order, so these override the main file; a later drop-in (99-*.conf)
+/// can still override them.
+fn drop_in(scheme: &ColorScheme) -> String {
+    let get = |key: &str| scheme.get_or_fallback(key);
+    let mut content = String::new();
+    content.push_str("# Dunst colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    content.push_str("[global]\n");
+    content.push_str(&format!(
+        "    frame_color = \"{}\"\n",
+        get("outline_variant")
+    ));
+    content.push_str("    separator_color = frame\n");
+    content.push_str(&format!("    highlight = \"{}\"\n", get("primary")));
+    for (section, background, foreground, frame) in [
+        (
+            "urgency_low",
+            "surface_container",
+            "on_surface_variant",
+            "outline_variant",
+        ),
+        (
+            "urgency_normal",
+            "surface_container",
+            "on_surface",
+            "outline_variant",
+        ),
+        (
+            "urgency_critical",
+            "error_container",
+            "on_error_container",
+            "error",
+        ),
+    ] {
+        content.push_str(&format!("\n[{}]\n", section));
+        content.push_str(&format!("    background = \"{}\"\n", get(background)));
+        content.push_str(&format!("    foreground = \"{}\"\n", get(foreground)));
+        content.push_str(&format!("    frame_color = \"{}\"\n", get(frame)));
+    }
+    content
+}
+
+/// Parse `dunst --version` ("Dunst - A customizable and lightweight
+/// notification-daemon v1.9.2 (2023-04-20)") into (major, minor).
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('v'))
+        .find(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+async fn dunst_version() -> Option<(u32, u32)> {
+    let output = tokio::process::Command::new("dunst")
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+async fn dunstctl_reload() -> bool {
+    tokio::process::Command::new("dunstctl")
+        .arg("reload")
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Whether the running dunst is the systemd unit (D-Bus activated or
+/// started by the session), which must be restarted through systemd: a
+/// killed unit is restarted or re-activated behind our back.
+async fn unit_active() -> bool {
+    tokio::process::Command::new("systemctl")
+        .args(["--user", "is-active", "--quiet", SYSTEMD_UNIT])
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+async fn restart_unit() -> Result<()> {
+    let output = tokio::process::Command::new("systemctl")
+        .args(["--user", "restart", SYSTEMD_UNIT])
+        .output()
+        .await
+        .map_err(|e| lmtt_core::Error::Module(format!("Failed to run systemctl: {}", e)))?;
+    if !output.status.success() {
+        return Err(lmtt_core::Error::Module(format!(
+            "systemctl --user restart {} failed: {}",
+            SYSTEMD_UNIT,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Last resort for a dunst started by hand or from the compositor's
+/// autostart: restart it, detached so it isn't a child of this short-lived
+/// process.
+async fn restart_dunst() -> Result<()> {
+    let _ = tokio::process::Command::new("pkill")
+        .args(["-x", "dunst"])
+        .output()
+        .await;
+
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(2);
+    while crate::process_running("dunst").await && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    }
+
+    tokio::process::Command::new("dunst")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| lmtt_core::Error::Module(format!("Failed to restart dunst: {}", e)))?;
+    Ok(())
+}
+
+#[async_trait]
+impl ThemeModule for DunstModule {
+    fn name(&self) -> &'static str {
+        "dunst"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "dunst"
+    }
+
+    /// Reloads after writer modules, like the other notification daemons.
+    fn priority(&self) -> u8 {
+        150
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let path = drop_in_path()?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        lmtt_core::fsutil::write_atomic(&path, drop_in(scheme)).await?;
+        tracing::info!("[Dunst] Updated colors at {}", path.display());
+
+        let version = dunst_version().await;
+        if version.is_some_and(|version| version < DROP_IN_VERSION) {
+            tracing::warn!(
+                "[Dunst] dunst < 1.9 ignores dunstrc.d; upgrade, or copy {} into dunstrc",
+                path.display()
+            );
+        }
+
+        if !crate::process_running("dunst").await {
+            tracing::debug!("[Dunst] Not running; wrote colors, skipping reload");
+            return Ok(());
+        }
+
+        // An unreadable version is tried as a current one.
+        if version.is_none_or(|version| version >= RELOAD_VERSION) && dunstctl_reload().await {
+            tracing::info!("[Dunst] Reloaded");
+        } else if unit_active().await {
+            restart_unit().await?;
+            tracing::info!(
+                "[Dunst] Restarted {} (dunstctl has no reload)",
+                SYSTEMD_UNIT
+            );
+        } else {
+            restart_dunst().await?;
+            tracing::info!("[Dunst] Restarted (dunstctl has no reload)");
+        }
+
+        Ok(())
+    }
+
+    /// The drop-in is picked up without touching dunstrc.
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_version;
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(
+            parse_version(
+                "Dunst - A customizable and lightweight notification-daemon v1.9.2 (2023-04-20)\n"
+            ),
+            Some((1, 9))
+        );
+        assert_eq!(
+            parse_version("Dunst - ... v1.11.0-12-gabcdef"),
+            Some((1, 11))
+        );
+        assert_eq!(parse_version(""), None);
+    }
+}
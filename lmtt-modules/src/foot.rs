@@ -0,0 +1,223 @@
+use crate::gtk::merge_ini;
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(FootModule);
+
+pub struct FootModule;
+
+impl Default for FootModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FootModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn foot_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("foot"))
+}
+
+/// foot.ini colors are bare hex, without the leading `#`.
+fn bare(hex: &str) -> String {
+    hex.trim_start_matches('#').to_string()
+}
+
+/// `~/`-relative when under $HOME (foot expands `~` in include paths).
+fn tilde_path(path: &std::path::Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(PathBuf::from)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// The `[colors]` section of lmtt-colors.ini, merged into whatever the file
+/// already holds so it round-trips like every other INI lmtt writes.
+fn colors_ini(existing: &str, scheme: &ColorScheme) -> String {
+    let term = terminal::term_colors(scheme);
+    let mut updates: Vec<(&str, String)> = vec![
+        ("foreground", bare(&term.foreground)),
+        ("background", bare(&term.background)),
+        ("selection-foreground", bare(&term.selection_fg)),
+        ("selection-background", bare(&term.selection_bg)),
+        (
+            "cursor",
+            format!("{} {}", bare(&term.cursor_fg), bare(&term.cursor_bg)),
+        ),
+    ];
+    const REGULAR: [&str; 8] = [
+        "regular0", "regular1", "regular2", "regular3", "regular4", "regular5", "regular6",
+        "regular7",
+    ];
+    const BRIGHT: [&str; 8] = [
+        "bright0", "bright1", "bright2", "bright3", "bright4", "bright5", "bright6", "bright7",
+    ];
+    updates.extend(REGULAR.into_iter().zip(term.ansi.iter().map(|c| bare(c))));
+    updates.extend(BRIGHT.into_iter().zip(term.brights.iter().map(|c| bare(c))));
+
+    let existing = if existing.trim().is_empty() {
+        "# Foot colors generated by lmtt\n"
+    } else {
+        existing
+    };
+    merge_ini(existing, "colors", &updates)
+}
+
+/// The ptys of running foot windows: the controlling terminal (fd 0) of
+/// every direct child of a `foot` process. Covers both standalone foot and
+/// `foot --server`, whose footclient windows are children of the server.
+async fn find_foot_ptys() -> Vec<String> {
+    let Ok(mut entries) = tokio::fs::read_dir("/proc").await else {
+        return Vec::new();
+    };
+
+    // (pid, ppid, comm) for every process we can read
+    let mut procs: Vec<(String, String, String)> = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let pid = entry.file_name().to_string_lossy().into_owned();
+        if !pid.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(stat) = tokio::fs::read_to_string(entry.path().join("stat")).await else {
+            continue;
+        };
+        // comm is parenthesised and may contain spaces; fields resume after
+        // the last ')': state, ppid, ...
+        let (Some(open), Some(close)) = (stat.find('('), stat.rfind(')')) else {
+            continue;
+        };
+        let comm = stat[open + 1..close].to_string();
+        let Some(ppid) = stat[close + 1..].split_whitespace().nth(1) else {
+            continue;
+        };
+        procs.push((pid, ppid.to_string(), comm));
+    }
+
+    let foot_pids: Vec<&str> = procs
+        .iter()
+        .filter(|(_, _, comm)| comm == "foot")
+        .map(|(pid, _, _)| pid.as_str())
+        .collect();
+
+    let mut ptys = Vec::new();
+    for (pid, _, _) in procs
+        .iter()
+        .filter(|(_, ppid, _)| foot_pids.contains(&ppid.as_str()))
+    {
+        if let Ok(tty) = tokio::fs::read_link(format!("/proc/{}/fd/0", pid)).await {
+            let tty = tty.to_string_lossy().into_owned();
+            if tty.starts_with("/dev/pts/") {
+                ptys.push(tty);
+            }
+        }
+    }
+    ptys.sort_unstable();
+    ptys.dedup();
+    ptys
+}
+
+/// Recolor every open foot window via OSC 4/10/11/12 (plus 17/19 for the
+/// selection) written to its pty. Per-pty failures are non-fatal.
+async fn apply_osc_to_ptys(osc: &str) {
+    let mut updated = 0;
+    for pty in find_foot_ptys().await {
+        if terminal::write_osc(&pty, osc).await {
+            updated += 1;
+        }
+    }
+    if updated > 0 {
+        tracing::info!("[Foot] Recolored {} live pty(s) via OSC", updated);
+    }
+}
+
+#[async_trait]
+impl ThemeModule for FootModule {
+    fn name(&self) -> &'static str {
+        "foot"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "foot"
+    }
+
+    fn supports_live_recolor(&self) -> bool {
+        true
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = foot_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-colors.ini");
+        let existing = tokio::fs::read_to_string(&colors_file)
+            .await
+            .unwrap_or_default();
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_ini(&existing, scheme)).await?;
+        tracing::info!("[Foot] Updated colors at {}", colors_file.display());
+
+        // foot has no config reload; new windows read the include, open
+        // ones are recolored in place.
+        apply_osc_to_ptys(&terminal::term_colors(scheme).osc_payload()).await;
+
+        Ok(())
+    }
+
+    async fn apply_frame(&self, scheme: &ColorScheme) -> Result<()> {
+        apply_osc_to_ptys(&terminal::term_colors(scheme).osc_payload()).await;
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let foot_ini = foot_dir()?.join("foot.ini");
+        if !foot_ini.exists() {
+            return Ok(vec![]);
+        }
+
+        // Injected at the top of the file, i.e. in the implicit [main]
+        // section where foot accepts `include`; anything the user sets in
+        // [colors] further down still wins.
+        let include_line = format!(
+            "include={}",
+            tilde_path(&foot_dir()?.join("lmtt-colors.ini"))
+        );
+        let content = tokio::fs::read_to_string(&foot_ini).await?;
+        let already_included = crate::is_included(&content, "lmtt-colors.ini");
+
+        Ok(vec![ConfigFileInfo {
+            path: foot_ini,
+            include_line,
+            description: "Include LMTT colors in foot.ini".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::colors_ini;
+    use lmtt_core::{ColorScheme, ThemeMode};
+
+    #[test]
+    fn colors_are_bare_hex_and_rewrite_in_place() {
+        let mut scheme = ColorScheme::new(ThemeMode::Dark);
+        scheme.set("surface".into(), "#101010".into());
+        let first = colors_ini("", &scheme);
+        assert!(first.contains("[colors]\nforeground = "));
+        assert!(first.contains("background = 101010\n"));
+        assert!(!first.contains("= #"));
+
+        scheme.set("surface".into(), "#202020".into());
+        let second = colors_ini(&first, &scheme);
+        assert!(second.contains("background = 202020\n"));
+        assert_eq!(second.matches("[colors]").count(), 1);
+        assert_eq!(second.lines().count(), first.lines().count());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
+use std::path::PathBuf;
+
+crate::register_module!(GhosttyModule);
+
+pub struct GhosttyModule;
+
+impl Default for GhosttyModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GhosttyModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// First release that reloads its config on SIGUSR2. Earlier versions have
+/// no handler, and SIGUSR2's default disposition terminates the process.
+const SIGUSR2_RELOAD_VERSION: (u32, u32) = (1, 2);
+
+fn ghostty_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("ghostty"))
+}
+
+fn theme_name(mode: ThemeMode) -> String {
+    format!("lmtt-{}", mode)
+}
+
+fn theme_file(scheme: &ColorScheme) -> String {
+    let term = terminal::term_colors(scheme);
+    let mut content = String::new();
+    content.push_str("# Ghostty theme generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    for (i, color) in term.ansi.iter().chain(term.brights.iter()).enumerate() {
+        content.push_str(&format!("palette = {}={}\n", i, color));
+    }
+    for (key, value) in [
+        ("background", &term.background),
+        ("foreground", &term.foreground),
+        ("cursor-color", &term.cursor_bg),
+        ("cursor-text", &term.cursor_fg),
+        ("selection-background", &term.selection_bg),
+        ("selection-foreground", &term.selection_fg),
+    ] {
+        content.push_str(&format!("{} = {}\n", key, value));
+    }
+    content
+}
+
+/// Parse `ghostty +version` output ("Ghostty 1.2.0", or a tip build like
+/// "Ghostty 1.2.0-main+abc123") into (major, minor).
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Ghostty "))?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+async fn supports_sigusr2_reload() -> bool {
+    let Ok(output) = tokio::process::Command::new("ghostty")
+        .arg("+version")
+        .output()
+        .await
+    else {
+        return false;
+    };
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .is_some_and(|version| version >= SIGUSR2_RELOAD_VERSION)
+}
+
+#[async_trait]
+impl ThemeModule for GhosttyModule {
+    fn name(&self) -> &'static str {
+        "ghostty"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "ghostty"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, config: &Config) -> Result<()> {
+        let themes_dir = ghostty_dir()?.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+
+        let current = themes_dir.join(theme_name(scheme.mode));
+        lmtt_core::fsutil::write_atomic(&current, theme_file(scheme)).await?;
+        tracing::info!("[Ghostty] Updated theme at {}", current.display());
+
+        // Both variants must exist: Ghostty rejects a `theme = light:…,dark:…`
+        // naming a missing file.
+        let other_mode = crate::opposite_mode(scheme.mode);
+        let other = themes_dir.join(theme_name(other_mode));
+        if let Some(other_scheme) = crate::companion_scheme(other_mode, config, &other).await {
+            lmtt_core::fsutil::write_atomic(&other, theme_file(&other_scheme)).await?;
+        }
+
+        if !crate::process_running("ghostty").await {
+            tracing::debug!("[Ghostty] Not running; wrote themes only");
+            return Ok(());
+        }
+        if !supports_sigusr2_reload().await {
+            tracing::info!(
+                "[Ghostty] Version predates SIGUSR2 reload; new windows get the theme, \
+                 press the reload_config keybind for open ones"
+            );
+            return Ok(());
+        }
+        let _ = tokio::process::Command::new("pkill")
+            .args(["-USR2", "-x", "ghostty"])
+            .output()
+            .await;
+        tracing::info!("[Ghostty] Reloaded config via SIGUSR2");
+
+        Ok(())
+    }
+
+    /// Ghostty applies the last `theme =` it reads, so a line at the top
+    /// would lose to any theme the user already sets.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let dir = ghostty_dir()?;
+        // Ghostty 1.2 added `config.ghostty`; `config` is still read.
+        let Some(config) = ["config.ghostty", "config"]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+        else {
+            return Ok(vec![]);
+        };
+
+        let include_line = "theme = light:lmtt-light,dark:lmtt-dark";
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = crate::is_included(&content, include_line);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line: include_line.to_string(),
+            description: "Use the LMTT light/dark themes in Ghostty".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_version;
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(
+            parse_version("Ghostty 1.2.0\n\nVersion\n  - version: 1.2.0\n"),
+            Some((1, 2))
+        );
+        assert_eq!(parse_version("Ghostty 1.1.3-main+0c5b4c1"), Some((1, 1)));
+        assert_eq!(parse_version("ghostty: not found"), None);
+    }
+}
@@ -0,0 +1,488 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+use toml_edit::{DocumentMut, Item};
+
+crate::register_module!(HelixModule);
+
+pub struct HelixModule;
+
+impl Default for HelixModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HelixModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Upstream installs the binary as `hx`; Arch and a few others ship it as
+/// `helix`.
+const BINARIES: [&str; 2] = ["hx", "helix"];
+
+fn helix_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("helix"))
+}
+
+/// Inline-table style spec for one scope: fg, bg and modifiers, each
+/// optional.
+struct Style<'a> {
+    fg: Option<&'a str>,
+    bg: Option<&'a str>,
+    modifiers: &'a [&'a str],
+}
+
+fn fg(color: &str) -> Style<'_> {
+    Style {
+        fg: Some(color),
+        bg: None,
+        modifiers: &[],
+    }
+}
+
+fn bg(color: &str) -> Style<'_> {
+    Style {
+        fg: None,
+        bg: Some(color),
+        modifiers: &[],
+    }
+}
+
+fn fg_bg<'a>(fg: &'a str, bg: &'a str) -> Style<'a> {
+    Style {
+        fg: Some(fg),
+        bg: Some(bg),
+        modifiers: &[],
+    }
+}
+
+fn with<'a>(style: Style<'a>, modifiers: &'a [&'a str]) -> Style<'a> {
+    Style { modifiers, ..style }
+}
+
+impl Style<'_> {
+    fn to_toml(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(fg) = self.fg {
+            parts.push(format!("fg = \"{}\"", fg));
+        }
+        if let Some(bg) = self.bg {
+            parts.push(format!("bg = \"{}\"", bg));
+        }
+        if !self.modifiers.is_empty() {
+            let modifiers: Vec<String> = self
+                .modifiers
+                .iter()
+                .map(|m| format!("\"{}\"", m))
+                .collect();
+            parts.push(format!("modifiers = [{}]", modifiers.join(", ")));
+        }
+        format!("{{ {} }}", parts.join(", "))
+    }
+}
+
+fn theme_toml(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+
+    let surface = c("surface");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let container_low = c("surface_container_low");
+    let container = c("surface_container");
+    let container_high = c("surface_container_high");
+    let container_highest = c("surface_container_highest");
+    let primary = c("primary");
+    let on_primary = c("on_primary");
+    let primary_container = c("primary_container");
+    let on_primary_container = c("on_primary_container");
+    let secondary = c("secondary");
+    let on_secondary = c("on_secondary");
+    let tertiary = c("tertiary");
+    let on_tertiary = c("on_tertiary");
+    let error = c("error");
+    let outline = c("outline");
+    let outline_variant = c("outline_variant");
+    let green = &term.ansi[2];
+    let yellow = &term.ansi[3];
+    let cyan = &term.ansi[6];
+
+    let diag = terminal::diagnostics(scheme);
+    let (warning, info, hint) = (&diag.warning, &diag.info, &diag.hint);
+
+    let underline = |color: &str| {
+        format!(
+            "{{ underline = {{ color = \"{}\", style = \"curl\" }} }}",
+            color
+        )
+    };
+
+    let scopes: Vec<(&str, String)> = vec![
+        // Editor UI
+        ("ui.background", bg(&surface).to_toml()),
+        ("ui.text", fg(&on_surface).to_toml()),
+        ("ui.text.focus", with(fg(&primary), &["bold"]).to_toml()),
+        ("ui.text.inactive", fg(&on_surface_variant).to_toml()),
+        ("ui.cursor", fg_bg(&surface, &outline).to_toml()),
+        ("ui.cursor.primary", fg_bg(&on_primary, &primary).to_toml()),
+        (
+            "ui.cursor.match",
+            with(bg(&container_highest), &["bold"]).to_toml(),
+        ),
+        ("ui.cursorline.primary", bg(&container_low).to_toml()),
+        ("ui.selection", bg(&container_high).to_toml()),
+        (
+            "ui.selection.primary",
+            fg_bg(&on_primary_container, &primary_container).to_toml(),
+        ),
+        ("ui.linenr", fg(&outline).to_toml()),
+        (
+            "ui.linenr.selected",
+            with(fg(&primary), &["bold"]).to_toml(),
+        ),
+        ("ui.gutter", bg(&surface).to_toml()),
+        ("ui.statusline", fg_bg(&on_surface, &container).to_toml()),
+        (
+            "ui.statusline.inactive",
+            fg_bg(&on_surface_variant, &container_low).to_toml(),
+        ),
+        (
+            "ui.statusline.normal",
+            with(fg_bg(&on_primary, &primary), &["bold"]).to_toml(),
+        ),
+        (
+            "ui.statusline.insert",
+            with(fg_bg(&on_secondary, &secondary), &["bold"]).to_toml(),
+        ),
+        (
+            "ui.statusline.select",
+            with(fg_bg(&on_tertiary, &tertiary), &["bold"]).to_toml(),
+        ),
+        (
+            "ui.bufferline",
+            fg_bg(&on_surface_variant, &container).to_toml(),
+        ),
+        (
+            "ui.bufferline.active",
+            fg_bg(&on_surface, &surface).to_toml(),
+        ),
+        ("ui.popup", fg_bg(&on_surface, &container).to_toml()),
+        ("ui.window", fg(&outline_variant).to_toml()),
+        ("ui.help", fg_bg(&on_surface, &container_high).to_toml()),
+        ("ui.menu", fg_bg(&on_surface, &container).to_toml()),
+        (
+            "ui.menu.selected",
+            fg_bg(&on_primary_container, &primary_container).to_toml(),
+        ),
+        ("ui.menu.scroll", fg_bg(&outline, &container).to_toml()),
+        ("ui.virtual.whitespace", fg(&outline_variant).to_toml()),
+        ("ui.virtual.indent-guide", fg(&outline_variant).to_toml()),
+        ("ui.virtual.ruler", bg(&container_low).to_toml()),
+        ("ui.virtual.inlay-hint", fg(&outline).to_toml()),
+        (
+            "ui.virtual.jump-label",
+            with(fg(&error), &["bold"]).to_toml(),
+        ),
+        // Diagnostics
+        ("error", fg(&error).to_toml()),
+        ("warning", fg(warning).to_toml()),
+        ("info", fg(info).to_toml()),
+        ("hint", fg(hint).to_toml()),
+        ("diagnostic.error", underline(&error)),
+        ("diagnostic.warning", underline(warning)),
+        ("diagnostic.info", underline(info)),
+        ("diagnostic.hint", underline(hint)),
+        (
+            "diagnostic.unnecessary",
+            with(fg(&outline), &["dim"]).to_toml(),
+        ),
+        (
+            "diagnostic.deprecated",
+            with(fg(&outline), &["crossed_out"]).to_toml(),
+        ),
+        // Syntax
+        ("comment", with(fg(&outline), &["italic"]).to_toml()),
+        ("keyword", fg(&primary).to_toml()),
+        ("keyword.control", with(fg(&primary), &["bold"]).to_toml()),
+        ("function", fg(&tertiary).to_toml()),
+        ("function.macro", fg(&secondary).to_toml()),
+        ("type", fg(&secondary).to_toml()),
+        ("type.builtin", fg(&secondary).to_toml()),
+        ("constructor", fg(&secondary).to_toml()),
+        ("namespace", fg(&secondary).to_toml()),
+        ("constant", fg(cyan).to_toml()),
+        ("constant.numeric", fg(cyan).to_toml()),
+        ("string", fg(green).to_toml()),
+        ("string.special", fg(cyan).to_toml()),
+        ("variable", fg(&on_surface).to_toml()),
+        ("variable.parameter", fg(&on_surface_variant).to_toml()),
+        ("variable.builtin", fg(&secondary).to_toml()),
+        ("variable.other.member", fg(&on_surface).to_toml()),
+        ("operator", fg(&on_surface_variant).to_toml()),
+        ("punctuation", fg(&on_surface_variant).to_toml()),
+        ("attribute", fg(&tertiary).to_toml()),
+        ("label", fg(&primary).to_toml()),
+        ("tag", fg(&primary).to_toml()),
+        ("special", fg(&tertiary).to_toml()),
+        // Markup
+        ("markup.heading", with(fg(&primary), &["bold"]).to_toml()),
+        ("markup.bold", with(fg(&on_surface), &["bold"]).to_toml()),
+        (
+            "markup.italic",
+            with(fg(&on_surface), &["italic"]).to_toml(),
+        ),
+        (
+            "markup.strikethrough",
+            with(fg(&outline), &["crossed_out"]).to_toml(),
+        ),
+        (
+            "markup.link.url",
+            with(fg(&tertiary), &["underlined"]).to_toml(),
+        ),
+        ("markup.link.text", fg(&primary).to_toml()),
+        (
+            "markup.quote",
+            with(fg(&on_surface_variant), &["italic"]).to_toml(),
+        ),
+        ("markup.raw", fg(green).to_toml()),
+        ("markup.list", fg(&secondary).to_toml()),
+        // Diffs and VCS gutter
+        ("diff.plus", fg(green).to_toml()),
+        ("diff.minus", fg(&error).to_toml()),
+        ("diff.delta", fg(yellow).to_toml()),
+    ];
+
+    let mut content = String::new();
+    content.push_str("# Helix theme generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    for (scope, style) in scopes {
+        content.push_str(&format!("\"{}\" = {}\n", scope, style));
+    }
+    content
+}
+
+fn parse_config(content: &str) -> Result<DocumentMut> {
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| lmtt_core::Error::Module(format!("helix config.toml does not parse: {}", e)))
+}
+
+fn theme_is_lmtt(content: &str) -> bool {
+    content
+        .parse::<DocumentMut>()
+        .ok()
+        .and_then(|doc| doc.get("theme").and_then(Item::as_str).map(|t| t == "lmtt"))
+        .unwrap_or(false)
+}
+
+/// Comment that keeps the user's own theme above `theme = "lmtt"`, so
+/// cleanup can put it back.
+const PREVIOUS_THEME: &str = "# lmtt: previous theme = ";
+
+/// A key prefix without lmtt's previous-theme lines, and the last value one
+/// of them recorded.
+fn split_previous(prefix: &str) -> (String, Option<toml_edit::Value>) {
+    let mut kept = String::new();
+    let mut previous = None;
+    for line in prefix.split_inclusive('\n') {
+        match line.trim().strip_prefix(PREVIOUS_THEME.trim_end()) {
+            Some(value) => previous = value.trim().parse().ok(),
+            None => kept.push_str(line),
+        }
+    }
+    (kept, previous)
+}
+
+fn theme_prefix(doc: &DocumentMut) -> String {
+    doc.key("theme")
+        .and_then(|key| key.leaf_decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Set the root `theme` key, replacing any existing value in place (keeping
+/// its comments) or adding it ahead of the first table. A theme the user had
+/// is recorded in a comment above the key; nothing else in the file changes.
+fn set_theme(content: &str) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    let mut previous = None;
+    match doc.get_mut("theme").and_then(Item::as_value_mut) {
+        Some(value) => {
+            if value.as_str() != Some("lmtt") {
+                previous = Some(value.clone().decorated("", "").to_string());
+            }
+            let decor = value.decor().clone();
+            *value = "lmtt".into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            doc.insert("theme", toml_edit::value("lmtt"));
+        }
+    }
+
+    if let Some(previous) = previous {
+        let (prefix, _) = split_previous(&theme_prefix(&doc));
+        if let Some(mut key) = doc.key_mut("theme") {
+            key.leaf_decor_mut()
+                .set_prefix(format!("{}{}{}\n", prefix, PREVIOUS_THEME, previous));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Undo `set_theme`: put back the theme it recorded, or drop the key and
+/// return Helix to its default theme. A theme the user set since is left
+/// alone.
+fn unset_theme(content: &str) -> Result<String> {
+    if !theme_is_lmtt(content) {
+        return Ok(content.to_string());
+    }
+    let mut doc = parse_config(content)?;
+    let (prefix, previous) = split_previous(&theme_prefix(&doc));
+    let Some(previous) = previous else {
+        doc.remove("theme");
+        return Ok(doc.to_string());
+    };
+
+    if let Some(value) = doc.get_mut("theme").and_then(Item::as_value_mut) {
+        let decor = value.decor().clone();
+        *value = previous;
+        *value.decor_mut() = decor;
+    }
+    if let Some(mut key) = doc.key_mut("theme") {
+        key.leaf_decor_mut().set_prefix(prefix);
+    }
+    Ok(doc.to_string())
+}
+
+#[async_trait]
+impl ThemeModule for HelixModule {
+    fn name(&self) -> &'static str {
+        "helix"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "hx"
+    }
+
+    fn is_installed(&self) -> bool {
+        BINARIES.iter().any(|bin| which::which(bin).is_ok())
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let themes_dir = helix_dir()?.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+        let theme_file = themes_dir.join("lmtt.toml");
+        lmtt_core::fsutil::write_atomic(&theme_file, theme_toml(scheme)).await?;
+        tracing::info!("[Helix] Updated theme at {}", theme_file.display());
+
+        // SIGUSR1 is Helix's :config-reload, which also re-reads the theme.
+        let mut reloaded = false;
+        for bin in BINARIES {
+            if crate::process_running(bin).await {
+                let _ = tokio::process::Command::new("pkill")
+                    .args(["-USR1", "-x", bin])
+                    .output()
+                    .await;
+                reloaded = true;
+            }
+        }
+        if reloaded {
+            tracing::info!("[Helix] Reloaded running editors via SIGUSR1");
+        } else {
+            tracing::debug!("[Helix] Not running; wrote theme only");
+        }
+
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config = helix_dir()?.join("config.toml");
+        if !config.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = theme_is_lmtt(&content);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line: "theme = \"lmtt\"".to_string(),
+            description: "Set the LMTT theme in Helix config.toml".to_string(),
+            already_included,
+        }])
+    }
+
+    /// `theme` is a single root key, so it is set in place with a TOML-aware
+    /// edit: a managed block would add a duplicate key (invalid TOML) or land
+    /// inside whatever table precedes it.
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = set_theme(&content)?;
+        if new_content != content {
+            lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = unset_theme(&content)?;
+        if new_content == content {
+            return Ok(false);
+        }
+        lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_theme, theme_is_lmtt, unset_theme};
+
+    #[test]
+    fn theme_is_set_in_place_and_restored() {
+        let conf = "theme = \"onedark\" # old favourite\n\n[editor]\nline-number = \"relative\"\n";
+        let set = set_theme(conf).unwrap();
+        assert_eq!(
+            set,
+            "# lmtt: previous theme = \"onedark\"\ntheme = \"lmtt\" # old favourite\n\n[editor]\nline-number = \"relative\"\n"
+        );
+        assert!(theme_is_lmtt(&set));
+        assert_eq!(set_theme(&set).unwrap(), set);
+        assert_eq!(unset_theme(&set).unwrap(), conf);
+    }
+
+    #[test]
+    fn theme_is_added_before_tables_and_removed() {
+        let conf = "# helix\n[editor]\nmouse = false\n";
+        let set = set_theme(conf).unwrap();
+        assert!(set.starts_with("theme = \"lmtt\"\n"));
+        assert!(set.contains("# helix\n[editor]\nmouse = false\n"));
+        assert!(theme_is_lmtt(&set));
+        // Only lmtt's own theme is removed
+        assert_eq!(unset_theme(conf).unwrap(), conf);
+        assert!(!theme_is_lmtt(&unset_theme(&set).unwrap()));
+    }
+}
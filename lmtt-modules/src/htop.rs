@@ -0,0 +1,112 @@
+use crate::ThemeModule;
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
+use std::path::PathBuf;
+
+crate::register_module!(HtopModule);
+
+/// htop has no custom colors, only built-in schemes: this picks the one
+/// made for the mode's terminal background.
+pub struct HtopModule;
+
+impl Default for HtopModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtopModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// htop's "Default" (for dark terminals) and "Light Terminal" schemes. Both
+/// draw on the terminal's own background, which lmtt already themes; "Black
+/// on White" and "Black Night" would paint over it.
+fn color_scheme(mode: ThemeMode) -> u8 {
+    match mode {
+        ThemeMode::Dark => 0,
+        ThemeMode::Light => 3,
+    }
+}
+
+fn htoprc() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("htop")
+        .join("htoprc"))
+}
+
+/// htoprc with `color_scheme=` replaced in place. htop rewrites the file
+/// itself, so the rest is kept exactly as it is.
+fn set_color_scheme(content: &str, scheme: u8) -> String {
+    let line = format!("color_scheme={}", scheme);
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|l| {
+            if l.starts_with("color_scheme=") && !found {
+                found = true;
+                line.clone()
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(line);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[async_trait]
+impl ThemeModule for HtopModule {
+    fn name(&self) -> &'static str {
+        "htop"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "htop"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        // htop writes a full htoprc on first exit; until then its defaults
+        // follow the terminal already.
+        let path = htoprc()?;
+        if !path.exists() {
+            tracing::debug!("[Htop] No htoprc yet; nothing to set");
+            return Ok(());
+        }
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        let updated = set_color_scheme(&content, color_scheme(scheme.mode));
+        if updated != content {
+            lmtt_core::fsutil::write_atomic(&path, updated).await?;
+        }
+
+        // Read at startup only; a running htop keeps its scheme.
+        tracing::info!(
+            "[Htop] Set color_scheme={} in {}",
+            color_scheme(scheme.mode),
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_scheme_follows_mode_in_place() {
+        let rc = "# Beware! This file is rewritten by htop when settings are changed in the interface.\nhighlight_base_name=1\ncolor_scheme=5\nenable_mouse=1\n";
+        let light = set_color_scheme(rc, color_scheme(ThemeMode::Light));
+        assert_eq!(light, rc.replace("color_scheme=5", "color_scheme=3"));
+        let dark = set_color_scheme(&light, color_scheme(ThemeMode::Dark));
+        assert_eq!(dark, rc.replace("color_scheme=5", "color_scheme=0"));
+    }
+}
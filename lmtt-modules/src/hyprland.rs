@@ -20,7 +20,7 @@ impl HyprlandModule {
 
 /// Whether the user runs with misc:disable_autoreload = 1. Defaults to false
 /// (autoreload on) when hyprctl can't be queried.
-async fn autoreload_disabled() -> bool {
+pub async fn autoreload_disabled() -> bool {
     let Ok(output) = tokio::process::Command::new("hyprctl")
         .args(["getoption", "misc:disable_autoreload", "-j"])
         .output()
@@ -40,7 +40,7 @@ async fn autoreload_disabled() -> bool {
 
 /// Hyprland's rgb() takes exactly 6 hex digits. Parse via hex_to_rgb (handles
 /// #rgb / #rrggbb / #rrggbbaa and is byte-safe) and re-emit canonical rrggbb.
-fn hypr_rgb(hex: &str) -> String {
+pub(crate) fn hypr_rgb(hex: &str) -> String {
     match lmtt_core::colors::hex_to_rgb(hex) {
         Ok((r, g, b)) => format!("{:02x}{:02x}{:02x}", r, g, b),
         Err(_) => "000000".to_string(),
@@ -61,6 +61,10 @@ impl ThemeModule for HyprlandModule {
         100
     }
 
+    fn supports_live_recolor(&self) -> bool {
+        true
+    }
+
     fn legacy_include_lines(&self) -> Vec<String> {
         // Pre-rename artifact: older lmtt wrote ~/.config/hypr/colors.conf and
         // injected this source line. On re-setup its block is stripped so the
@@ -152,7 +156,9 @@ impl ThemeModule for HyprlandModule {
         // HDR/multi-head setups that stalls the compositor for seconds — so
         // only reload explicitly when autoreload is disabled OR the Lua config
         // is active (where autoreload won't pick up the dofile'd colors).
-        if lua_config_active || autoreload_disabled().await {
+        if crate::process_running("Hyprland").await
+            && (lua_config_active || autoreload_disabled().await)
+        {
             let reload = tokio::process::Command::new("hyprctl")
                 .arg("reload")
                 .output()
@@ -172,6 +178,30 @@ impl ThemeModule for HyprlandModule {
         Ok(())
     }
 
+    /// Frames set the window borders directly with `hyprctl keyword`, using
+    /// the same values as $lmtt_active_border / $lmtt_inactive_border; the
+    /// final apply's config reload then restores the config's own values.
+    async fn apply_frame(&self, scheme: &ColorScheme) -> Result<()> {
+        let batch = format!(
+            "keyword general:col.active_border rgb({}) rgb({}) 45deg ; \
+             keyword general:col.inactive_border rgb({})",
+            hypr_rgb(&scheme.get_or_fallback("primary")),
+            hypr_rgb(&scheme.get_or_fallback("secondary")),
+            hypr_rgb(&scheme.get_or_fallback("outline")),
+        );
+        let output = tokio::process::Command::new("hyprctl")
+            .args(["--batch", &batch])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(lmtt_core::Error::Module(format!(
+                "hyprctl --batch failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
     async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
         let config_dir =
             dirs::config_dir().ok_or(lmtt_core::Error::Config("No config dir".into()))?;
@@ -0,0 +1,119 @@
+use crate::hyprland::hypr_rgb;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(HyprlockModule);
+
+pub struct HyprlockModule;
+
+impl Default for HyprlockModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyprlockModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn hypr_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("hypr"))
+}
+
+/// Every token as `$lmtt_<token>`, plus named roles for the usual hyprlock
+/// widgets so a config can be themed without picking tokens.
+fn colors_conf(scheme: &ColorScheme) -> String {
+    let get = |key: &str| format!("rgb({})", hypr_rgb(&scheme.get_or_fallback(key)));
+    let mut content = String::new();
+    content.push_str("# Hyprlock colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n", scheme.mode));
+    content.push_str("# Reference these in hyprlock.conf, e.g.\n");
+    content.push_str("#   input-field {\n");
+    content.push_str("#       outer_color = $lmtt_input_outer\n");
+    content.push_str("#       inner_color = $lmtt_input_inner\n");
+    content.push_str("#       font_color = $lmtt_input_font\n");
+    content.push_str("#   }\n\n");
+
+    for (key, _) in crate::sorted_colors(scheme) {
+        content.push_str(&format!("$lmtt_{} = {}\n", key, get(key)));
+    }
+
+    content.push('\n');
+    for (role, token) in [
+        ("background", "surface"),
+        ("text", "on_surface"),
+        ("text_muted", "on_surface_variant"),
+        ("input_outer", "primary"),
+        ("input_inner", "surface_container_high"),
+        ("input_font", "on_surface"),
+        ("input_check", "tertiary"),
+        ("input_fail", "error"),
+        ("input_capslock", "secondary"),
+    ] {
+        content.push_str(&format!("$lmtt_{} = {}\n", role, get(token)));
+    }
+    content
+}
+
+#[async_trait]
+impl ThemeModule for HyprlockModule {
+    fn name(&self) -> &'static str {
+        "hyprlock"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "hyprlock"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = hypr_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-hyprlock.conf");
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_conf(scheme)).await?;
+
+        // hyprlock reads its config each time the screen locks.
+        tracing::info!("[Hyprlock] Updated colors at {}", colors_file.display());
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let hyprlock_conf = hypr_dir()?.join("hyprlock.conf");
+        if !hyprlock_conf.exists() {
+            return Ok(vec![]);
+        }
+
+        // hyprlang variables must be defined before use: the default
+        // top-of-file block is the right place.
+        let include_line = "source = ~/.config/hypr/lmtt-hyprlock.conf";
+        let content = tokio::fs::read_to_string(&hyprlock_conf).await?;
+        let already_included = crate::is_included(&content, "lmtt-hyprlock.conf");
+
+        Ok(vec![ConfigFileInfo {
+            path: hyprlock_conf,
+            include_line: include_line.to_string(),
+            description: "Source lmtt colors in hyprlock config".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::colors_conf;
+    use lmtt_core::{ColorScheme, ThemeMode};
+
+    #[test]
+    fn tokens_and_roles_are_hyprlang_variables() {
+        let mut scheme = ColorScheme::new(ThemeMode::Light);
+        scheme.set("primary".into(), "#3a5f8a".into());
+        let conf = colors_conf(&scheme);
+        assert!(conf.contains("$lmtt_primary = rgb(3a5f8a)\n"));
+        assert!(conf.contains("$lmtt_input_outer = rgb(3a5f8a)\n"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ConfigFileInfo, ThemeModule};
+use crate::{process_running, ConfigFileInfo, ThemeModule};
 use async_trait::async_trait;
 use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
 use serde_json::{Map, Value};
@@ -105,12 +105,3 @@ impl ThemeModule for HyprPanelModule {
         Ok(vec![])
     }
 }
-
-async fn process_running(name: &str) -> bool {
-    tokio::process::Command::new("pgrep")
-        .args(["-x", name])
-        .output()
-        .await
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
@@ -0,0 +1,307 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+
+crate::register_module!(KittyModule);
+
+pub struct KittyModule;
+
+impl Default for KittyModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KittyModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn kitty_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("kitty"))
+}
+
+fn colors_conf(scheme: &ColorScheme) -> String {
+    let term = terminal::term_colors(scheme);
+    let primary = scheme.get_or_fallback("primary");
+    let on_primary = scheme.get_or_fallback("on_primary");
+    let tab_bg = scheme.get_or_fallback("surface_container");
+
+    let mut content = String::new();
+    content.push_str("# Kitty colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    for (key, value) in [
+        ("foreground", &term.foreground),
+        ("background", &term.background),
+        ("selection_foreground", &term.selection_fg),
+        ("selection_background", &term.selection_bg),
+        ("cursor", &term.cursor_bg),
+        ("cursor_text_color", &term.cursor_fg),
+        ("url_color", &primary),
+        ("active_border_color", &primary),
+        (
+            "inactive_border_color",
+            &scheme.get_or_fallback("outline_variant"),
+        ),
+        ("active_tab_foreground", &on_primary),
+        ("active_tab_background", &primary),
+        (
+            "inactive_tab_foreground",
+            &scheme.get_or_fallback("on_surface_variant"),
+        ),
+        ("inactive_tab_background", &tab_bg),
+        ("tab_bar_background", &tab_bg),
+    ] {
+        content.push_str(&format!("{} {}\n", key, value));
+    }
+    content.push('\n');
+    for (i, color) in term.ansi.iter().chain(term.brights.iter()).enumerate() {
+        content.push_str(&format!("color{} {}\n", i, color));
+    }
+    content
+}
+
+/// The effective `listen_on` from kitty.conf (last one wins, as in kitty),
+/// without its `unix:` scheme. TCP listeners are ignored — lmtt only talks
+/// to local sockets.
+fn configured_listen_on(kitty_conf: &str) -> Option<String> {
+    kitty_conf
+        .lines()
+        .rev()
+        .find_map(|line| {
+            let mut parts = line.trim().splitn(2, char::is_whitespace);
+            (parts.next() == Some("listen_on")).then(|| parts.next().unwrap_or("").trim())
+        })
+        .and_then(|value| value.strip_prefix("unix:"))
+        .map(expand_listen_on)
+}
+
+/// kitty expands `~` and environment variables in listen_on.
+fn expand_listen_on(value: &str) -> String {
+    let mut out = match (value.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => format!("{}/{}", home.display(), rest),
+        _ => value.to_string(),
+    };
+    while let Some(start) = out.find("${") {
+        let Some(len) = out[start..].find('}') else {
+            break;
+        };
+        let name = &out[start + 2..start + len];
+        let Ok(val) = std::env::var(name) else {
+            break;
+        };
+        out.replace_range(start..start + len + 1, &val);
+    }
+    out
+}
+
+/// Whether a socket name belongs to a listen_on pattern. kitty replaces
+/// `{kitty_pid}` with its pid, or appends `-<pid>` when the placeholder is
+/// absent (a `--listen-on` given on the command line is used verbatim).
+fn matches_listen_on(name: &str, pattern: &str) -> bool {
+    let is_pid = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if let Some((prefix, suffix)) = pattern.split_once("{kitty_pid}") {
+        return name.len() >= prefix.len() + suffix.len()
+            && name.starts_with(prefix)
+            && name.ends_with(suffix)
+            && is_pid(&name[prefix.len()..name.len() - suffix.len()]);
+    }
+    name == pattern
+        || name
+            .strip_prefix(pattern)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(is_pid)
+}
+
+/// Remote-control addresses (`unix:...`) of running kitty instances: sockets
+/// matching kitty.conf's listen_on, plus any socket named `kitty*` in
+/// $XDG_RUNTIME_DIR or the temp dir (the usual `--listen-on` locations).
+async fn find_kitty_sockets(listen_on: Option<&str>) -> Vec<String> {
+    let mut sockets = Vec::new();
+
+    let mut scans: Vec<(PathBuf, String)> = Vec::new();
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        scans.push((PathBuf::from(runtime_dir), "kitty".to_string()));
+    }
+    scans.push((std::env::temp_dir(), "kitty".to_string()));
+
+    match listen_on {
+        // Abstract sockets have no file; the kernel lists them with a leading @
+        Some(abstract_name) if abstract_name.starts_with('@') => {
+            let table = tokio::fs::read_to_string("/proc/net/unix")
+                .await
+                .unwrap_or_default();
+            for path in table
+                .lines()
+                .skip(1)
+                .filter_map(|l| l.split_whitespace().nth(7))
+            {
+                if matches_listen_on(path, abstract_name) {
+                    sockets.push(format!("unix:{}", path));
+                }
+            }
+        }
+        Some(path) => {
+            let path = Path::new(path);
+            if let (Some(dir), Some(name)) = (path.parent(), path.file_name()) {
+                scans.push((dir.to_path_buf(), name.to_string_lossy().into_owned()));
+            }
+        }
+        None => {}
+    }
+
+    for (dir, pattern) in scans {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
+            if !file_type.is_socket() {
+                continue;
+            }
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let matched = if pattern == "kitty" {
+                name.starts_with("kitty")
+            } else {
+                matches_listen_on(&name, &pattern)
+            };
+            if matched {
+                sockets.push(format!("unix:{}", entry.path().display()));
+            }
+        }
+    }
+
+    sockets.sort();
+    sockets.dedup();
+    sockets
+}
+
+#[async_trait]
+impl ThemeModule for KittyModule {
+    fn name(&self) -> &'static str {
+        "kitty"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "kitty"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = kitty_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-colors.conf");
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_conf(scheme)).await?;
+        tracing::info!("[Kitty] Updated colors at {}", colors_file.display());
+
+        if !crate::process_running("kitty").await {
+            tracing::debug!("[Kitty] Not running; wrote colors only");
+            return Ok(());
+        }
+
+        let kitty_conf = tokio::fs::read_to_string(dir.join("kitty.conf"))
+            .await
+            .unwrap_or_default();
+        let listen_on = configured_listen_on(&kitty_conf);
+
+        // `kitten @` is the modern remote-control client; kitty < 0.28 only
+        // ships `kitty @`.
+        let program = if which::which("kitten").is_ok() {
+            "kitten"
+        } else {
+            "kitty"
+        };
+
+        let mut updated = 0;
+        for socket in find_kitty_sockets(listen_on.as_deref()).await {
+            // --all: every window, not just the active one. --configured:
+            // also replace the configured colors, so new windows and a later
+            // config reload keep the new palette.
+            let mut cmd = tokio::process::Command::new(program);
+            cmd.args(["@", "--to", &socket, "set-colors", "--all", "--configured"])
+                .arg(&colors_file)
+                .kill_on_drop(true);
+            match tokio::time::timeout(std::time::Duration::from_secs(2), cmd.output()).await {
+                Ok(Ok(output)) if output.status.success() => updated += 1,
+                Ok(Ok(output)) => tracing::debug!(
+                    "[Kitty] {} rejected set-colors (remote control off or password-protected?): {}",
+                    socket,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                Ok(Err(e)) => tracing::debug!("[Kitty] Failed to run {}: {}", program, e),
+                Err(_) => tracing::debug!("[Kitty] Timed out on {}", socket),
+            }
+        }
+
+        if updated > 0 {
+            tracing::info!(
+                "[Kitty] Recolored {} instance(s) via remote control",
+                updated
+            );
+            return Ok(());
+        }
+
+        // No reachable socket: SIGUSR1 makes kitty re-read kitty.conf, which
+        // includes the colors file. Slower and config-wide, hence the fallback.
+        let _ = tokio::process::Command::new("pkill")
+            .args(["-USR1", "-x", "kitty"])
+            .output()
+            .await;
+        tracing::info!("[Kitty] Reloaded config via SIGUSR1");
+
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let kitty_conf = kitty_dir()?.join("kitty.conf");
+        if !kitty_conf.exists() {
+            return Ok(vec![]);
+        }
+
+        let include_line = "include lmtt-colors.conf";
+        let content = tokio::fs::read_to_string(&kitty_conf).await?;
+        let already_included = crate::is_included(&content, include_line);
+
+        Ok(vec![ConfigFileInfo {
+            path: kitty_conf,
+            include_line: include_line.to_string(),
+            description: "Include LMTT colors in kitty.conf".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{configured_listen_on, matches_listen_on};
+
+    #[test]
+    fn listen_on_pid_suffix() {
+        assert!(matches_listen_on("mykitty-1234", "mykitty"));
+        assert!(matches_listen_on("mykitty", "mykitty"));
+        assert!(!matches_listen_on("mykitty-abc", "mykitty"));
+        assert!(!matches_listen_on("mykitty2-1234", "mykitty"));
+        assert!(matches_listen_on(
+            "kitty-1234.sock",
+            "kitty-{kitty_pid}.sock"
+        ));
+        assert!(!matches_listen_on("kitty-.sock", "kitty-{kitty_pid}.sock"));
+        assert!(matches_listen_on("@kitty-99", "@kitty"));
+    }
+
+    #[test]
+    fn listen_on_last_unix_wins() {
+        let conf = "listen_on unix:/tmp/first\n# listen_on unix:/tmp/commented\nlisten_on unix:/tmp/second\n";
+        assert_eq!(configured_listen_on(conf).as_deref(), Some("/tmp/second"));
+        assert_eq!(configured_listen_on("listen_on tcp:localhost:0\n"), None);
+        assert_eq!(configured_listen_on("font_size 11\n"), None);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(LazygitModule);
+
+/// Sets lmtt's `gui.theme` keys in config.yml, each marked `# lmtt`. A key
+/// the user had set is kept as a `# lmtt-replaced:` comment and restored by
+/// `lmtt cleanup`; their other keys and comments are left alone.
+///
+/// The edit is line based, not a YAML round trip: `gui` and `gui.theme` must
+/// be plain block mappings. An anchor, alias, tag or flow value on either
+/// (`gui: &x`, `theme: {...}`) is reported as an error instead of rewritten.
+pub struct LazygitModule;
+
+impl Default for LazygitModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LazygitModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// `$LG_CONFIG_FILE` when it names a single file, else lazygit's default
+/// `config.yml`.
+fn config_yml() -> Result<PathBuf> {
+    if let Some(file) = std::env::var("LG_CONFIG_FILE")
+        .ok()
+        .filter(|f| !f.is_empty() && !f.contains(','))
+    {
+        return Ok(PathBuf::from(file));
+    }
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("lazygit")
+        .join("config.yml"))
+}
+
+/// `gui.theme` entries: lazygit takes a list of a color and attributes.
+fn theme_entries(scheme: &ColorScheme) -> Vec<(&'static str, String)> {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    vec![
+        ("activeBorderColor", format!("['{}', bold]", c("primary"))),
+        ("inactiveBorderColor", format!("['{}']", c("outline"))),
+        (
+            "searchingActiveBorderColor",
+            format!("['{}', bold]", c("tertiary")),
+        ),
+        ("optionsTextColor", format!("['{}']", c("primary"))),
+        (
+            "selectedLineBgColor",
+            format!("['{}']", c("surface_container_high")),
+        ),
+        (
+            "inactiveViewSelectedLineBgColor",
+            format!("['{}']", c("surface_container")),
+        ),
+        (
+            "cherryPickedCommitFgColor",
+            format!("['{}']", c("on_tertiary_container")),
+        ),
+        (
+            "cherryPickedCommitBgColor",
+            format!("['{}']", c("tertiary_container")),
+        ),
+        (
+            "markedBaseCommitFgColor",
+            format!("['{}']", c("on_secondary_container")),
+        ),
+        (
+            "markedBaseCommitBgColor",
+            format!("['{}']", c("secondary_container")),
+        ),
+        ("unstagedChangesColor", format!("['{}']", c("error"))),
+        ("defaultFgColor", format!("['{}']", c("on_surface"))),
+    ]
+}
+
+/// Marks the lines lmtt owns.
+const OWNED: &str = "# lmtt";
+/// Prefixes a user line lmtt's value replaced.
+const REPLACED: &str = "# lmtt-replaced: ";
+const INCLUDE_LINE: &str = "gui.theme: lmtt colors, marked `# lmtt`";
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Blank and comment-only lines don't open or close YAML blocks.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn is_owned(line: &str) -> bool {
+    line.trim_end().ends_with(OWNED)
+}
+
+/// An owned `gui:` or `theme:` line lmtt added because it was missing.
+fn is_owned_header(line: &str) -> bool {
+    line.trim_end()
+        .strip_suffix(OWNED)
+        .is_some_and(|rest| rest.trim_end().ends_with(':'))
+}
+
+/// The key of a `key: ...` mapping line.
+fn mapping_key(line: &str) -> Option<&str> {
+    if !is_content(line) {
+        return None;
+    }
+    let (key, rest) = line.trim_start().split_once(':')?;
+    (!key.starts_with('-') && (rest.is_empty() || rest.starts_with(' '))).then_some(key)
+}
+
+/// Whether a `key:` line opens a block mapping. Anything on the same line
+/// (a flow value, an anchor, a tag) can't be edited line by line.
+fn opens_block(line: &str, key: &str) -> Result<()> {
+    let value = line.trim_start()[key.len() + 1..].trim();
+    if value.is_empty() || value.starts_with('#') {
+        return Ok(());
+    }
+    Err(lmtt_core::Error::Module(format!(
+        "lazygit config.yml: `{}: {}` is not a plain block mapping, which lmtt needs to set gui.theme",
+        key, value
+    )))
+}
+
+/// End (exclusive) of the block under `lines[start]`: the line after its
+/// last content line indented deeper than `indent`, or a `- ` item at the
+/// same indent. Trailing blank and comment lines stay with what follows.
+fn block_end(lines: &[String], start: usize, indent: usize) -> usize {
+    let mut end = start + 1;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if !is_content(line) {
+            continue;
+        }
+        let line_indent = indent_of(line);
+        if line_indent < indent || (line_indent == indent && !line.trim_start().starts_with('-')) {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+/// The `key:` line at `indent` within `range`.
+fn find_key(
+    lines: &[String],
+    range: std::ops::Range<usize>,
+    indent: usize,
+    key: &str,
+) -> Option<usize> {
+    range
+        .into_iter()
+        .find(|&i| indent_of(&lines[i]) == indent && mapping_key(&lines[i]) == Some(key))
+}
+
+/// Indent of the first content line in `range`.
+fn child_indent(lines: &[String], range: std::ops::Range<usize>) -> Option<usize> {
+    lines[range]
+        .iter()
+        .find(|l| is_content(l))
+        .map(|l| indent_of(l))
+}
+
+fn owned_line(indent: usize, key: &str, value: &str) -> String {
+    format!("{}{}: {} {}", " ".repeat(indent), key, value, OWNED)
+}
+
+fn join_lines(lines: Vec<String>) -> String {
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+/// Whether setup has put lmtt's theme keys in place.
+fn theme_is_lmtt(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| is_owned(line) && !is_owned_header(line))
+}
+
+/// `lines` without anything lmtt added, and with the user lines it replaced
+/// restored. Headers it added go too, unless the user put keys under them.
+fn strip_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if let Some(original) = line.trim_start().strip_prefix(REPLACED) {
+            lines.push(format!("{}{}", " ".repeat(indent_of(line)), original));
+        } else if !is_owned(line) || is_owned_header(line) {
+            lines.push(line.to_string());
+        }
+    }
+    // Bottom up, so an emptied `theme:` goes before its `gui:` is checked
+    for i in (0..lines.len()).rev() {
+        if !is_owned_header(&lines[i]) {
+            continue;
+        }
+        let has_children = lines[i + 1..]
+            .iter()
+            .find(|l| is_content(l))
+            .is_some_and(|l| indent_of(l) > indent_of(&lines[i]));
+        if !has_children {
+            lines.remove(i);
+        }
+    }
+    lines
+}
+
+/// Setup's edit: every entry set as an owned line under `gui.theme`, after
+/// the user's own value for that key (commented out) if there is one.
+fn set_theme_keys(content: &str, entries: &[(&str, String)]) -> Result<String> {
+    let mut lines = strip_lines(content);
+    let owned = |indent: usize| entries.iter().map(move |(k, v)| owned_line(indent, k, v));
+
+    let Some(gui) = find_key(&lines, 0..lines.len(), 0, "gui") else {
+        lines.push(format!("gui: {}", OWNED));
+        lines.push(format!("  theme: {}", OWNED));
+        lines.extend(owned(4));
+        return Ok(join_lines(lines));
+    };
+    opens_block(&lines[gui], "gui")?;
+    let gui_end = block_end(&lines, gui, 0);
+    let step = child_indent(&lines, gui + 1..gui_end).unwrap_or(2);
+
+    let Some(theme) = find_key(&lines, gui + 1..gui_end, step, "theme") else {
+        let mut block = vec![format!("{}theme: {}", " ".repeat(step), OWNED)];
+        block.extend(owned(step * 2));
+        lines.splice(gui + 1..gui + 1, block);
+        return Ok(join_lines(lines));
+    };
+    opens_block(&lines[theme], "theme")?;
+    let theme_end = block_end(&lines, theme, step);
+    let indent = child_indent(&lines, theme + 1..theme_end).unwrap_or(step * 2);
+
+    for (key, value) in entries {
+        let theme_end = block_end(&lines, theme, step);
+        let at = match find_key(&lines, theme + 1..theme_end, indent, key) {
+            Some(start) => {
+                let end = block_end(&lines, start, indent);
+                for line in lines[start..end].iter_mut().filter(|l| is_content(l)) {
+                    *line = format!("{}{}{}", " ".repeat(indent), REPLACED, &line[indent..]);
+                }
+                end
+            }
+            None => theme_end,
+        };
+        lines.insert(at, owned_line(indent, key, value));
+    }
+    Ok(join_lines(lines))
+}
+
+/// A switch's edit: new values on the owned lines, nothing else touched.
+fn update_theme_keys(content: &str, entries: &[(&str, String)]) -> String {
+    let lines = content
+        .lines()
+        .map(|line| {
+            let entry = mapping_key(line)
+                .filter(|_| is_owned(line))
+                .and_then(|key| entries.iter().find(|(k, _)| *k == key));
+            match entry {
+                Some((key, value)) => owned_line(indent_of(line), key, value),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    join_lines(lines)
+}
+
+/// The scheme setup writes before the first switch: the last one applied,
+/// else the fallback palette.
+fn current_scheme() -> ColorScheme {
+    lmtt_core::tokens::load_current().unwrap_or_else(|_| {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.colors = lmtt_core::fallback::fallback_colors(scheme.mode);
+        scheme
+    })
+}
+
+#[async_trait]
+impl ThemeModule for LazygitModule {
+    fn name(&self) -> &'static str {
+        "lazygit"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "lazygit"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let path = config_yml()?;
+        if !path.exists() {
+            tracing::debug!("[Lazygit] No config.yml; nothing to color");
+            return Ok(());
+        }
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        if !theme_is_lmtt(&content) {
+            tracing::debug!("[Lazygit] gui.theme not set up; run `lmtt setup`");
+            return Ok(());
+        }
+        let updated = update_theme_keys(&content, &theme_entries(scheme));
+        if updated != content {
+            lmtt_core::fsutil::write_atomic(&path, updated).await?;
+        }
+
+        // lazygit reloads config.yml when it changes.
+        tracing::info!("[Lazygit] Updated gui.theme in {}", path.display());
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let path = config_yml()?;
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        let already_included = theme_is_lmtt(&content);
+
+        Ok(vec![ConfigFileInfo {
+            path,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Set lmtt colors in lazygit's gui.theme".to_string(),
+            already_included,
+        }])
+    }
+
+    /// `gui.theme` keys are edited in place: a marker block would repeat
+    /// `gui` (invalid YAML) or land inside whatever mapping precedes it.
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = set_theme_keys(&content, &theme_entries(&current_scheme()))?;
+        if new_content != content {
+            lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        if !content.contains(OWNED) {
+            return Ok(false);
+        }
+        let new_content = join_lines(strip_lines(&content));
+        if new_content == content {
+            return Ok(false);
+        }
+        lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(active: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("activeBorderColor", format!("['{}', bold]", active)),
+            ("defaultFgColor", "['#222222']".to_string()),
+        ]
+    }
+
+    #[test]
+    fn only_owned_theme_keys_are_set_and_restored() {
+        let conf = "# my lazygit\ngui:\n    nerdFontsVersion: \"3\"\n    theme:\n        activeBorderColor:\n            - green\n            - bold\n        # picked by hand\n        selectedLineBgColor: [blue]\n\n    showIcons: true\ngit:\n    paging:\n        pager: delta\n";
+        let set = set_theme_keys(conf, &entries("#111111")).unwrap();
+        assert_eq!(
+            set,
+            "# my lazygit\ngui:\n    nerdFontsVersion: \"3\"\n    theme:\n        # lmtt-replaced: activeBorderColor:\n        # lmtt-replaced:     - green\n        # lmtt-replaced:     - bold\n        activeBorderColor: ['#111111', bold] # lmtt\n        # picked by hand\n        selectedLineBgColor: [blue]\n        defaultFgColor: ['#222222'] # lmtt\n\n    showIcons: true\ngit:\n    paging:\n        pager: delta\n"
+        );
+        assert!(theme_is_lmtt(&set));
+        assert_eq!(set_theme_keys(&set, &entries("#111111")).unwrap(), set);
+
+        let switched = update_theme_keys(&set, &entries("#333333"));
+        assert_eq!(set.replace("#111111", "#333333"), switched);
+        assert_eq!(join_lines(strip_lines(&switched)), conf);
+    }
+
+    #[test]
+    fn missing_gui_and_theme_are_added_and_removed() {
+        let conf = "gui:\n  showIcons: true\n";
+        let set = set_theme_keys(conf, &entries("#111111")).unwrap();
+        assert_eq!(
+            set,
+            "gui:\n  theme: # lmtt\n    activeBorderColor: ['#111111', bold] # lmtt\n    defaultFgColor: ['#222222'] # lmtt\n  showIcons: true\n"
+        );
+        assert_eq!(join_lines(strip_lines(&set)), conf);
+
+        let conf = "git:\n  autoFetch: false\n";
+        let set = set_theme_keys(conf, &entries("#111111")).unwrap();
+        assert!(set.starts_with(
+            "git:\n  autoFetch: false\ngui: # lmtt\n  theme: # lmtt\n    activeBorderColor:"
+        ));
+        assert_eq!(join_lines(strip_lines(&set)), conf);
+
+        // A key the user adds under an added header keeps it
+        let kept = set.replace("  theme: # lmtt\n", "  showIcons: true\n  theme: # lmtt\n");
+        assert_eq!(
+            join_lines(strip_lines(&kept)),
+            "git:\n  autoFetch: false\ngui: # lmtt\n  showIcons: true\n"
+        );
+    }
+
+    #[test]
+    fn non_block_mappings_are_rejected() {
+        assert!(set_theme_keys("gui: {showIcons: true}\n", &entries("#111111")).is_err());
+        assert!(set_theme_keys("gui: &gui\n  showIcons: true\n", &entries("#111111")).is_err());
+        assert!(set_theme_keys("gui:\n  theme: *colors\n", &entries("#111111")).is_err());
+    }
+}
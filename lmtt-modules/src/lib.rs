@@ -1,16 +1,36 @@
+pub mod alacritty;
+pub mod bash;
+pub mod bat;
+pub mod btop;
 pub mod cleanup;
 pub mod custom;
+pub mod dircolors;
+pub mod dunst;
 pub mod fish;
+pub mod foot;
 pub mod fuzzel;
+pub mod ghostty;
 pub mod gtk;
+pub mod helix;
+pub mod htop;
 pub mod hyprland;
+pub mod hyprlock;
 pub mod hyprpanel;
+pub mod kitty;
+pub mod lazygit;
+pub mod mako;
+pub mod niri;
 pub mod nvim;
 pub mod qt;
 pub mod registry;
+pub mod rofi;
 pub mod setup;
+pub mod shell;
 pub mod slint;
+pub mod starship;
+pub mod sway;
 pub mod swaync;
+pub mod terminal;
 pub mod tmux;
 pub mod vscode;
 pub mod waybar;
@@ -18,6 +38,9 @@ pub mod wezterm;
 pub mod wofi;
 pub mod xdg;
 pub mod xfconf;
+pub mod zed;
+pub mod zellij;
+pub mod zsh;
 
 use async_trait::async_trait;
 use lmtt_core::{ColorScheme, Config, Result};
@@ -39,7 +62,7 @@ fn comment_style(path: &Path) -> Option<(&'static str, &'static str)> {
         Some("css") | Some("scss") => Some(("/* ", " */")),
         Some("lua") => Some(("-- ", "")),
         Some("json") => None,
-        Some("jsonc") | Some("json5") => Some(("// ", "")),
+        Some("jsonc") | Some("json5") | Some("kdl") | Some("rasi") => Some(("// ", "")),
         _ => Some(("# ", "")), // conf/ini/toml/yaml/fish/tmux/hypr/…
     }
 }
@@ -55,6 +78,76 @@ fn is_comment_line(line: &str) -> bool {
         || t.starts_with(';')
 }
 
+/// Whether a process with exactly this name is running (`pgrep -x`, so
+/// `kitty` doesn't match `kitty-helper`). `name` is a pgrep pattern, so
+/// `a|b` matches either. Built-in modules check this before signalling,
+/// messaging or restarting a running app, so `lmtt restore` at login only
+/// writes files for apps that aren't up yet. Steps that store the setting
+/// itself (gsettings, fish `set -U`, `bat cache --build`) or only read
+/// (`niri validate`) run either way.
+pub async fn process_running(name: &str) -> bool {
+    tokio::process::Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// The scheme's colors by token name, sorted so an unchanged scheme
+/// rewrites a byte-identical file.
+pub(crate) fn sorted_colors(scheme: &ColorScheme) -> Vec<(&String, &String)> {
+    let mut colors: Vec<_> = scheme.colors.iter().collect();
+    colors.sort();
+    colors
+}
+
+/// The other mode.
+pub(crate) fn opposite_mode(mode: lmtt_core::ThemeMode) -> lmtt_core::ThemeMode {
+    match mode {
+        lmtt_core::ThemeMode::Light => lmtt_core::ThemeMode::Dark,
+        lmtt_core::ThemeMode::Dark => lmtt_core::ThemeMode::Light,
+    }
+}
+
+/// Colors for the mode NOT being switched to, for modules that write a
+/// light/dark pair so the app can follow the system color-scheme on its own.
+/// Prefers the matugen colors cached for the current wallpaper; otherwise
+/// returns the fallback palette only when `existing` (the variant's output
+/// file) is missing, so a variant from an earlier switch is kept.
+pub(crate) async fn companion_scheme(
+    mode: lmtt_core::ThemeMode,
+    config: &Config,
+    existing: &Path,
+) -> Option<ColorScheme> {
+    if config.general.use_matugen && config.cache.enabled {
+        if let Ok(cache) = lmtt_core::cache::Cache::from_config(config) {
+            if let Ok(Some(colors)) = cache
+                .get_cached_colors(
+                    Path::new(&config.general.wallpaper),
+                    &mode.to_string(),
+                    &config.general.scheme_type,
+                )
+                .await
+            {
+                let mut scheme = ColorScheme::new(mode);
+                for (key, value) in colors.into_iter().chain(config.colors.colors.clone()) {
+                    if lmtt_core::colors::is_hex_color(&value) {
+                        scheme.set(key, value);
+                    }
+                }
+                return Some(scheme);
+            }
+        }
+    }
+    if existing.exists() {
+        return None;
+    }
+    let mut scheme = ColorScheme::new(mode);
+    scheme.colors = lmtt_core::fallback::fallback_colors(mode);
+    Some(scheme)
+}
+
 /// Whether the config already has THIS module's integration active — the
 /// include line appears inside an lmtt managed block, or on a non-comment
 /// line the user added manually. Crucially this is keyed on the include line,
@@ -146,6 +239,103 @@ fn strip_owned_blocks(content: &str, owned: &[&str]) -> Result<String> {
     Ok(out.join("\n"))
 }
 
+/// `base` followed by a blank line and `block`. Removal round-trips: the
+/// block's trailing blank line is what strip_owned_blocks swallows.
+pub(crate) fn append_block(base: &str, block: &str) -> String {
+    if base.trim().is_empty() {
+        return block.to_string();
+    }
+    let mut out = base.to_string();
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push('\n');
+    out.push_str(block);
+    out
+}
+
+/// The default `inject_config`, for modules that override it only for some
+/// of their config files.
+pub(crate) async fn inject_managed_block(
+    path: &Path,
+    include_line: &str,
+    legacy: &[String],
+    at_end: bool,
+) -> Result<()> {
+    if !path.exists() {
+        return Err(lmtt_core::Error::Module(format!(
+            "Config file not found: {}",
+            path.display()
+        )));
+    }
+
+    let content = tokio::fs::read_to_string(path).await?;
+    let new_content = with_managed_block(path, &content, include_line, legacy, at_end)?;
+    if new_content == content {
+        return Ok(());
+    }
+    lmtt_core::fsutil::write_atomic(path, new_content).await
+}
+
+/// `content` of the config file at `path` with the managed block holding
+/// `include_line` added, or unchanged if it is already included. For
+/// modules that check the result before writing it.
+pub(crate) fn with_managed_block(
+    path: &Path,
+    content: &str,
+    include_line: &str,
+    legacy: &[String],
+    at_end: bool,
+) -> Result<String> {
+    if is_included(content, include_line) {
+        return Ok(content.to_string());
+    }
+
+    let Some((prefix, suffix)) = comment_style(path) else {
+        return Err(lmtt_core::Error::Module(format!(
+            "Refusing to inject into strict JSON ({}) — it has no comment syntax; use JSONC or configure it manually",
+            path.display()
+        )));
+    };
+
+    // Migration: strip this module's own stale blocks/lines (old include
+    // lines) before adding the current one, so an upgrade doesn't leave a
+    // block sourcing a file this version no longer writes.
+    let mut owned: Vec<&str> = vec![include_line];
+    owned.extend(legacy.iter().map(|s| s.as_str()));
+    let base = strip_owned_blocks(content, &owned)?;
+
+    let block =
+        format!("{prefix}{MARKER_START}{suffix}\n{include_line}\n{prefix}{MARKER_END}{suffix}\n");
+    Ok(if at_end {
+        append_block(&base, &block)
+    } else {
+        format!("{block}\n{base}")
+    })
+}
+
+/// The default `remove_config`; see `ThemeModule::remove_config`.
+pub(crate) async fn remove_managed_blocks(
+    path: &Path,
+    include_line: &str,
+    legacy: &[String],
+) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false); // Already gone
+    }
+
+    let content = tokio::fs::read_to_string(path).await?;
+    let mut owned: Vec<&str> = vec![include_line];
+    owned.extend(legacy.iter().map(|s| s.as_str()));
+
+    let new_content = strip_owned_blocks(&content, &owned)?;
+    if new_content == content {
+        return Ok(false); // Nothing of ours to remove
+    }
+    lmtt_core::fsutil::write_atomic(path, new_content).await?;
+    Ok(true)
+}
+
 /// Information about a config file that needs lmtt integration
 #[derive(Debug, Clone)]
 pub struct ConfigFileInfo {
@@ -185,45 +375,23 @@ pub trait ThemeModule: Send + Sync {
         Ok(vec![])
     }
 
+    /// Whether the managed block goes at the END of the config file instead
+    /// of the top: for formats where later settings override earlier ones,
+    /// so an include at the top would be shadowed by the user's own values.
+    fn include_at_end(&self) -> bool {
+        false
+    }
+
     /// Inject include line into config file, wrapped in marker comments that
     /// use the file type's actual comment syntax.
     async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
-        let path = &config_file.path;
-
-        if !path.exists() {
-            return Err(lmtt_core::Error::Module(format!(
-                "Config file not found: {}",
-                path.display()
-            )));
-        }
-
-        let content = tokio::fs::read_to_string(path).await?;
-
-        if is_included(&content, &config_file.include_line) {
-            return Ok(());
-        }
-
-        let Some((prefix, suffix)) = comment_style(path) else {
-            return Err(lmtt_core::Error::Module(format!(
-                "Refusing to inject into strict JSON ({}) — it has no comment syntax; use JSONC or configure it manually",
-                path.display()
-            )));
-        };
-
-        // Migration: strip this module's own stale blocks/lines (old include
-        // lines) before adding the current one, so an upgrade doesn't leave a
-        // block sourcing a file this version no longer writes.
-        let legacy = self.legacy_include_lines();
-        let mut owned: Vec<&str> = vec![config_file.include_line.as_str()];
-        owned.extend(legacy.iter().map(|s| s.as_str()));
-        let base = strip_owned_blocks(&content, &owned)?;
-
-        let new_content = format!(
-            "{prefix}{MARKER_START}{suffix}\n{}\n{prefix}{MARKER_END}{suffix}\n\n{base}",
-            config_file.include_line
-        );
-
-        lmtt_core::fsutil::write_atomic(path, new_content).await
+        inject_managed_block(
+            &config_file.path,
+            &config_file.include_line,
+            &self.legacy_include_lines(),
+            self.include_at_end(),
+        )
+        .await
     }
 
     /// Remove THIS module's lmtt-injected config (for cleanup): only managed
@@ -234,26 +402,19 @@ pub trait ThemeModule: Send + Sync {
     /// Returns Ok(true) if it actually changed the file, Ok(false) if there
     /// was nothing of this module's to remove (so callers don't over-report).
     async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
-        let path = &config_file.path;
-
-        if !path.exists() {
-            return Ok(false); // Already gone
-        }
-
-        let content = tokio::fs::read_to_string(path).await?;
-        let mut owned: Vec<&str> = vec![config_file.include_line.as_str()];
-        let legacy = self.legacy_include_lines();
-        owned.extend(legacy.iter().map(|s| s.as_str()));
-
-        let new_content = strip_owned_blocks(&content, &owned)?;
-        if new_content == content {
-            return Ok(false); // Nothing of ours to remove
-        }
-        lmtt_core::fsutil::write_atomic(path, new_content).await?;
-        Ok(true)
+        remove_managed_blocks(
+            &config_file.path,
+            &config_file.include_line,
+            &self.legacy_include_lines(),
+        )
+        .await
     }
 
-    /// Optional: Module-specific health check
+    /// Optional: Module-specific health check, run by `lmtt doctor`. The
+    /// error message is shown to the user as-is, so it should name the fix.
+    /// Only for real breakage: `lmtt doctor` reports config files that don't
+    /// include lmtt's colors itself, as a warning, since leaving an app out
+    /// of setup is a valid choice.
     async fn health_check(&self) -> Result<()> {
         Ok(())
     }
@@ -273,6 +434,25 @@ pub trait ThemeModule: Send + Sync {
         None
     }
 
+    /// Whether `apply_frame` can recolor the running app in place, cheaply
+    /// enough to run several times a second. Only these modules receive the
+    /// intermediate frames of a `switch.transition_ms` fade.
+    fn supports_live_recolor(&self) -> bool {
+        false
+    }
+
+    /// Push one intermediate transition frame to the running app without
+    /// touching config files. The final colors always arrive via `apply`.
+    async fn apply_frame(&self, _scheme: &ColorScheme) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether this module was loaded from a user/system module definition
+    /// (`~/.config/lmtt/modules/*.toml`) rather than compiled in.
+    fn is_custom(&self) -> bool {
+        false
+    }
+
     /// Include lines this module used in a PREVIOUS version. On inject the
     /// blocks/lines matching these are removed first, so an upgrade that
     /// renames the sourced file (e.g. Hyprland colors.conf → lmtt-colors.conf)
@@ -381,6 +561,70 @@ mod inject_tests {
         assert_eq!(removed, body);
     }
 
+    #[tokio::test]
+    async fn kdl_uses_slash_comments_and_round_trips() {
+        let body = "default_layout \"compact\"\n";
+        let (injected, removed) = round_trip("config.kdl", body, "theme \"lmtt\"").await;
+        assert!(
+            injected.starts_with("// >>> lmtt managed block"),
+            "KDL must get // markers, got: {}",
+            injected
+        );
+        assert_eq!(removed, body);
+    }
+
+    #[tokio::test]
+    async fn rasi_uses_slash_comments_and_round_trips() {
+        let body = "configuration {\n    modi: \"drun\";\n}\n";
+        let (injected, removed) =
+            round_trip("config.rasi", body, "@import \"lmtt-colors.rasi\"").await;
+        assert!(injected.starts_with("// >>> lmtt managed block"));
+        assert_eq!(removed, body);
+    }
+
+    #[tokio::test]
+    async fn appended_block_round_trips() {
+        struct AppendingModule;
+
+        #[async_trait]
+        impl ThemeModule for AppendingModule {
+            fn name(&self) -> &'static str {
+                "appending"
+            }
+            fn binary_name(&self) -> &'static str {
+                "appending"
+            }
+            fn include_at_end(&self) -> bool {
+                true
+            }
+            async fn apply(&self, _s: &ColorScheme, _c: &Config) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = std::env::temp_dir().join(format!("lmtt-append-test-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.rasi");
+        let body = "configuration {}\n@theme \"arthur\"\n";
+        tokio::fs::write(&path, body).await.unwrap();
+        let info = ConfigFileInfo {
+            path: path.clone(),
+            include_line: "@import \"lmtt-colors.rasi\"".to_string(),
+            description: String::new(),
+            already_included: false,
+        };
+
+        AppendingModule.inject_config(&info).await.unwrap();
+        let injected = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(injected.starts_with(body));
+        assert!(injected.ends_with("// <<< lmtt managed block <<<\n"));
+
+        AppendingModule.remove_config(&info).await.unwrap();
+        let removed = tokio::fs::read_to_string(&path).await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+        assert_eq!(removed, body);
+    }
+
     #[tokio::test]
     async fn conf_uses_hash_comments_and_round_trips() {
         let body = "monitor = ,preferred,auto,1\n";
@@ -0,0 +1,118 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(MakoModule);
+
+pub struct MakoModule;
+
+impl Default for MakoModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MakoModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn mako_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("mako"))
+}
+
+/// Global colors plus urgency sections. Mako merges an included file's
+/// criteria sections like its own, and anything the user sets after the
+/// include still wins.
+fn colors_config(scheme: &ColorScheme) -> String {
+    let get = |key: &str| scheme.get_or_fallback(key);
+    let mut content = String::new();
+    content.push_str("# Mako colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    content.push_str(&format!("background-color={}\n", get("surface_container")));
+    content.push_str(&format!("text-color={}\n", get("on_surface")));
+    content.push_str(&format!("border-color={}\n", get("outline_variant")));
+    content.push_str(&format!(
+        "progress-color=over {}\n",
+        get("primary_container")
+    ));
+    content.push_str("\n[urgency=low]\n");
+    content.push_str(&format!("text-color={}\n", get("on_surface_variant")));
+    content.push_str("\n[urgency=high]\n");
+    content.push_str(&format!("background-color={}\n", get("error_container")));
+    content.push_str(&format!("text-color={}\n", get("on_error_container")));
+    content.push_str(&format!("border-color={}\n", get("error")));
+    content
+}
+
+#[async_trait]
+impl ThemeModule for MakoModule {
+    fn name(&self) -> &'static str {
+        "mako"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "mako"
+    }
+
+    /// Reloads after writer modules, like the other notification daemons.
+    fn priority(&self) -> u8 {
+        150
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = mako_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-colors");
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_config(scheme)).await?;
+        tracing::info!("[Mako] Updated colors at {}", colors_file.display());
+
+        // Installed-but-idle daemons are common (mako next to swaync); a
+        // makoctl call with no mako on the bus just errors.
+        if !crate::process_running("mako").await {
+            tracing::debug!("[Mako] Not running; wrote colors, skipping reload");
+            return Ok(());
+        }
+
+        let output = tokio::process::Command::new("makoctl")
+            .arg("reload")
+            .output()
+            .await
+            .map_err(|e| lmtt_core::Error::Module(format!("makoctl failed to run: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(lmtt_core::Error::Module(format!(
+                "makoctl reload failed: {}",
+                stderr.trim()
+            )));
+        }
+
+        tracing::info!("[Mako] Reloaded");
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config = mako_dir()?.join("config");
+        if !config.exists() {
+            return Ok(vec![]);
+        }
+
+        // Injected at the top, i.e. among the global options, where mako
+        // accepts `include` (1.7+). Absolute, as the file is written under
+        // $XDG_CONFIG_HOME, which need not be ~/.config.
+        let include_line = format!("include={}", mako_dir()?.join("lmtt-colors").display());
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = crate::is_included(&content, &include_line);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line,
+            description: "Include lmtt colors in mako config".to_string(),
+            already_included,
+        }])
+    }
+}
@@ -0,0 +1,527 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::{Path, PathBuf};
+
+crate::register_module!(NiriModule);
+
+pub struct NiriModule;
+
+impl Default for NiriModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NiriModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// First release that understands `include`.
+const INCLUDE_VERSION: (u32, u32, u32) = (0, 1, 10);
+
+const INCLUDE_LINE: &str = "include \"lmtt-colors.kdl\"";
+
+/// Ends every line lmtt adds to config.kdl for niri without `include`.
+const INLINE_TAG: &str = "// lmtt-inline";
+
+/// Starts a user line that an inline color replaced, commented out so it
+/// can be put back.
+const REPLACED_TAG: &str = "// lmtt-replaced: ";
+
+/// The layout sections lmtt colors, and the token behind each color.
+const SECTIONS: [(&str, [(&str, &str); 2]); 2] = [
+    (
+        "focus-ring",
+        [
+            ("active-color", "primary"),
+            ("inactive-color", "surface_container_highest"),
+        ],
+    ),
+    (
+        "border",
+        [
+            ("active-color", "primary"),
+            ("inactive-color", "outline_variant"),
+        ],
+    ),
+];
+
+fn niri_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("niri"))
+}
+
+/// Focus ring and border colors only: whether either is drawn, and how
+/// wide, stays with the user's own `layout` section.
+fn layout_kdl(scheme: &ColorScheme) -> String {
+    let mut content = String::new();
+    content.push_str("layout {\n");
+    for (section, colors) in SECTIONS {
+        content.push_str(&format!("    {} {{\n", section));
+        for (key, token) in colors {
+            content.push_str(&format!(
+                "        {} \"{}\"\n",
+                key,
+                scheme.get_or_fallback(token)
+            ));
+        }
+        content.push_str("    }\n");
+    }
+    content.push_str("}\n");
+    content
+}
+
+fn colors_kdl(scheme: &ColorScheme) -> String {
+    format!(
+        "// niri colors generated by lmtt\n// Mode: {}\n{}",
+        scheme.mode,
+        layout_kdl(scheme)
+    )
+}
+
+/// A line without its `//` comment, outside string literals.
+fn code_of(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '/' if !in_string && line[i..].starts_with("//") => return line[..i].trim(),
+            _ => {}
+        }
+    }
+    line.trim()
+}
+
+/// Braces a line opens minus the ones it closes.
+fn depth_change(code: &str) -> i32 {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut change = 0;
+    for c in code.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => change += 1,
+            '}' if !in_string => change -= 1,
+            _ => {}
+        }
+    }
+    change
+}
+
+fn node_name(code: &str) -> &str {
+    code.split(|c: char| c.is_whitespace() || c == '{')
+        .next()
+        .unwrap_or("")
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// The children block of the node opened on `lines[open]`: the index of its
+/// closing line. Only `name … {` and `}` on lines of their own are edited.
+fn block_close(lines: &[String], open: usize) -> Result<usize> {
+    let unsupported = || {
+        lmtt_core::Error::Module(format!(
+            "niri config.kdl: write `{}` with its `{{` and `}}` on lines of their own so lmtt can set its colors",
+            node_name(code_of(&lines[open]))
+        ))
+    };
+    if !code_of(&lines[open]).ends_with('{') || depth_change(code_of(&lines[open])) != 1 {
+        return Err(unsupported());
+    }
+    let mut depth = 1;
+    for (i, line) in lines.iter().enumerate().skip(open + 1) {
+        let code = code_of(line);
+        depth += depth_change(code);
+        if depth == 0 {
+            return if code == "}" {
+                Ok(i)
+            } else {
+                Err(unsupported())
+            };
+        }
+    }
+    Err(unsupported())
+}
+
+/// Direct children of the node opened on `lines[open]`, as (index, name).
+fn children(lines: &[String], open: usize, close: usize) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate().take(close).skip(open + 1) {
+        let code = code_of(line);
+        if depth == 0 && !code.is_empty() {
+            found.push((i, node_name(code)));
+        }
+        depth += depth_change(code);
+    }
+    found
+}
+
+/// config.kdl without anything the inline colors added: tagged lines are
+/// dropped and the user's lines they replaced are put back.
+fn strip_inline(content: &str) -> String {
+    let mut out = String::new();
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\n', '\r']);
+        if body.trim_end().ends_with(INLINE_TAG) {
+            continue;
+        }
+        match body.trim_start().strip_prefix(REPLACED_TAG) {
+            Some(original) => {
+                out.push_str(indent_of(body));
+                out.push_str(original);
+                out.push_str(&line[body.len()..]);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+/// config.kdl with the focus-ring and border colors merged into the user's
+/// `layout` section (niri takes a single one), or a new tagged `layout`
+/// appended when there is none. A color the user set is commented out next
+/// to lmtt's, and everything else in the file is left as it is. KDL is
+/// edited line by line: the sections involved need their braces on lines
+/// of their own.
+fn with_inline_colors(content: &str, scheme: &ColorScheme) -> Result<String> {
+    let base = strip_inline(content);
+    let mut lines: Vec<String> = base.lines().map(str::to_string).collect();
+    let tag = |line: String| format!("{} {}", line, INLINE_TAG);
+
+    let mut depth = 0;
+    let mut layout = None;
+    for (i, line) in lines.iter().enumerate() {
+        let code = code_of(line);
+        if depth == 0 && node_name(code) == "layout" {
+            layout = Some(i);
+            break;
+        }
+        depth += depth_change(code);
+    }
+
+    let Some(layout) = layout else {
+        lines.extend(layout_kdl(scheme).lines().map(|l| tag(l.to_string())));
+        let mut out = lines.join("\n");
+        out.push('\n');
+        return Ok(out);
+    };
+
+    let layout_close = block_close(&lines, layout)?;
+    let layout_indent = indent_of(&lines[layout]).to_string();
+    let child_indent = children(&lines, layout, layout_close)
+        .first()
+        .map(|(i, _)| indent_of(&lines[*i]).to_string())
+        .unwrap_or_else(|| format!("{}    ", layout_indent));
+    let step = " ".repeat(
+        child_indent
+            .len()
+            .saturating_sub(layout_indent.len())
+            .max(1),
+    );
+
+    // Edits run bottom-up so earlier indices stay valid.
+    let mut sections: Vec<_> = SECTIONS
+        .iter()
+        .map(|(name, colors)| {
+            let open = children(&lines, layout, layout_close)
+                .into_iter()
+                .find(|(_, child)| child == name)
+                .map(|(i, _)| i);
+            (open, *name, colors)
+        })
+        .collect();
+    sections.sort_by_key(|(open, _, _)| std::cmp::Reverse(*open));
+
+    let mut new_sections = Vec::new();
+    for (open, name, colors) in sections {
+        let Some(open) = open else {
+            new_sections.push(tag(format!("{}{} {{", child_indent, name)));
+            for (key, token) in colors {
+                new_sections.push(tag(format!(
+                    "{}{}{} \"{}\"",
+                    child_indent,
+                    step,
+                    key,
+                    scheme.get_or_fallback(token)
+                )));
+            }
+            new_sections.push(tag(format!("{}}}", child_indent)));
+            continue;
+        };
+
+        let close = block_close(&lines, open)?;
+        let existing = children(&lines, open, close)
+            .into_iter()
+            .map(|(i, child)| (i, child.to_string()))
+            .collect::<Vec<_>>();
+        let color_indent = existing
+            .first()
+            .map(|(i, _)| indent_of(&lines[*i]).to_string())
+            .unwrap_or_else(|| format!("{}{}", child_indent, step));
+        let mut replaced = Vec::new();
+        let mut missing = Vec::new();
+        for (key, token) in colors {
+            let value = format!("{} \"{}\"", key, scheme.get_or_fallback(token));
+            match existing.iter().find(|(_, child)| child == key) {
+                Some((i, _)) => replaced.push((*i, value)),
+                None => missing.push(tag(format!("{}{}", color_indent, value))),
+            }
+        }
+        // Bottom-up again, whatever order the user wrote the colors in
+        replaced.sort_by_key(|(i, _)| std::cmp::Reverse(*i));
+        lines.splice(close..close, missing);
+        for (i, value) in replaced {
+            let indent = indent_of(&lines[i]).to_string();
+            let original = lines[i].trim_start().to_string();
+            lines[i] = format!("{}{}{}", indent, REPLACED_TAG, original);
+            lines.insert(i + 1, tag(format!("{}{}", indent, value)));
+        }
+    }
+    let layout_close = block_close(&lines, layout)?;
+    lines.splice(layout_close..layout_close, new_sections);
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Parse `niri --version` ("niri 25.05.1 (b94a5db)", "niri 0.1.10 (…)").
+fn parse_version(output: &str) -> Option<(u32, u32, u32)> {
+    let version = output.trim().strip_prefix("niri ")?;
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+/// Unknown versions are assumed current.
+async fn supports_include() -> bool {
+    let Ok(output) = tokio::process::Command::new("niri")
+        .arg("--version")
+        .output()
+        .await
+    else {
+        return true;
+    };
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .is_none_or(|version| version >= INCLUDE_VERSION)
+}
+
+/// Check a config with `niri validate` before it goes live: niri reloads on
+/// every write, and a config it rejects leaves the session on stale colors
+/// with an error banner. The copy sits in the niri dir so the user's own
+/// relative includes resolve as they will for the real file.
+async fn validate(dir: &Path, content: &str) -> Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    let path = dir.join(format!(".lmtt-validate-{}.kdl", std::process::id()));
+    tokio::fs::write(&path, content).await?;
+    let output = tokio::process::Command::new("niri")
+        .args(["validate", "-c"])
+        .arg(&path)
+        .output()
+        .await;
+    let _ = tokio::fs::remove_file(&path).await;
+
+    let output =
+        output.map_err(|e| lmtt_core::Error::Module(format!("niri failed to run: {}", e)))?;
+    if !output.status.success() {
+        // niri reports config errors on stderr, with the spans on stdout
+        let mut message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if message.is_empty() {
+            message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        }
+        return Err(lmtt_core::Error::Module(format!(
+            "niri validate rejected the colors, nothing written: {}",
+            message
+        )));
+    }
+    Ok(())
+}
+
+/// Write `lmtt-colors.kdl`, validated standalone: a lone layout section is
+/// a complete config.
+async fn write_colors(dir: &Path, scheme: &ColorScheme) -> Result<PathBuf> {
+    let colors = colors_kdl(scheme);
+    validate(dir, &colors).await?;
+    let colors_file = dir.join("lmtt-colors.kdl");
+    lmtt_core::fsutil::write_atomic(&colors_file, colors).await?;
+    Ok(colors_file)
+}
+
+#[async_trait]
+impl ThemeModule for NiriModule {
+    fn name(&self) -> &'static str {
+        "niri"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "niri"
+    }
+
+    /// niri merges included sections, later values winning: the include has
+    /// to follow the user's own `layout`, or its colors are shadowed.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = niri_dir()?;
+        let config_kdl = dir.join("config.kdl");
+        let content = tokio::fs::read_to_string(&config_kdl)
+            .await
+            .unwrap_or_default();
+
+        if !supports_include().await {
+            if !config_kdl.exists() {
+                tracing::debug!("[Niri] No config.kdl; nothing to color");
+                return Ok(());
+            }
+            let updated = with_inline_colors(&content, scheme)?;
+            if updated != content {
+                validate(&dir, &updated).await?;
+                lmtt_core::fsutil::write_atomic(&config_kdl, updated).await?;
+            }
+            tracing::info!("[Niri] Updated inline colors in {}", config_kdl.display());
+            return Ok(());
+        }
+
+        let colors_file = write_colors(&dir, scheme).await?;
+        tracing::info!("[Niri] Updated colors at {}", colors_file.display());
+
+        // After a niri upgrade and re-setup, drop the inline colors the older
+        // version needed; they would otherwise keep overriding the include.
+        if content.contains(INLINE_TAG) && crate::is_included(&content, INCLUDE_LINE) {
+            let updated = strip_inline(&content);
+            validate(&dir, &updated).await?;
+            lmtt_core::fsutil::write_atomic(&config_kdl, updated).await?;
+            tracing::info!("[Niri] Removed inline colors superseded by the include");
+        }
+
+        Ok(())
+    }
+
+    /// The default block, checked with `niri validate` before it lands:
+    /// niri reloads config.kdl as soon as it changes. The colors file is
+    /// written first if no switch has yet, so the include never points at a
+    /// missing file.
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let dir = niri_dir()?;
+        if !dir.join("lmtt-colors.kdl").exists() {
+            // The next switch rewrites it with the real palette
+            let scheme = lmtt_core::tokens::load_current().unwrap_or_else(|_| {
+                let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+                scheme.colors = lmtt_core::fallback::fallback_colors(scheme.mode);
+                scheme
+            });
+            write_colors(&dir, &scheme).await?;
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let updated = crate::with_managed_block(
+            path,
+            &content,
+            &config_file.include_line,
+            &self.legacy_include_lines(),
+            self.include_at_end(),
+        )?;
+        if updated == content {
+            return Ok(());
+        }
+        validate(&dir, &updated).await?;
+        lmtt_core::fsutil::write_atomic(path, updated).await
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config_kdl = niri_dir()?.join("config.kdl");
+        // Without include support apply() maintains an inline block instead
+        if !config_kdl.exists() || !supports_include().await {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config_kdl).await?;
+        let already_included = crate::is_included(&content, INCLUDE_LINE);
+
+        Ok(vec![ConfigFileInfo {
+            path: config_kdl,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Include lmtt colors in niri config.kdl".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(parse_version("niri 25.05.1 (b94a5db)\n"), Some((25, 5, 1)));
+        assert_eq!(
+            parse_version("niri 0.1.10 (unknown commit)"),
+            Some((0, 1, 10))
+        );
+        assert!(parse_version("niri 0.1.9").unwrap() < INCLUDE_VERSION);
+        assert_eq!(parse_version("command not found"), None);
+    }
+
+    #[test]
+    fn inline_colors_are_appended_without_a_layout() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#111111".into());
+        let config = "input {\n    keyboard {}\n}\n";
+        let first = with_inline_colors(config, &scheme).unwrap();
+        assert!(first.starts_with(config));
+        assert!(first.contains("layout { // lmtt-inline\n"));
+        assert!(first.contains("active-color \"#111111\" // lmtt-inline"));
+
+        scheme.set("primary".into(), "#222222".into());
+        let second = with_inline_colors(&first, &scheme).unwrap();
+        assert_eq!(second.matches("layout {").count(), 1);
+        assert!(second.contains("active-color \"#222222\""));
+        assert!(!second.contains("#111111"));
+        assert_eq!(strip_inline(&second), config);
+    }
+
+    #[test]
+    fn inline_colors_merge_into_the_existing_layout() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#111111".into());
+        scheme.set("surface_container_highest".into(), "#333333".into());
+        scheme.set("outline_variant".into(), "#444444".into());
+        let config = "layout {\n  gaps 16\n  focus-ring {\n    width 4\n    active-color \"#7fc8ff\" // blue\n  }\n}\n\nbinds {\n  Mod+Q { close-window; }\n}\n";
+        let merged = with_inline_colors(config, &scheme).unwrap();
+        assert_eq!(
+            merged,
+            "layout {\n  gaps 16\n  focus-ring {\n    width 4\n    // lmtt-replaced: active-color \"#7fc8ff\" // blue\n    active-color \"#111111\" // lmtt-inline\n    inactive-color \"#333333\" // lmtt-inline\n  }\n  border { // lmtt-inline\n    active-color \"#111111\" // lmtt-inline\n    inactive-color \"#444444\" // lmtt-inline\n  } // lmtt-inline\n}\n\nbinds {\n  Mod+Q { close-window; }\n}\n"
+        );
+        assert_eq!(with_inline_colors(&merged, &scheme).unwrap(), merged);
+        assert_eq!(strip_inline(&merged), config);
+
+        assert!(with_inline_colors("layout { gaps 16; }\n", &scheme).is_err());
+    }
+}
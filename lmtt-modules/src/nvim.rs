@@ -1,3 +1,4 @@
+use crate::terminal;
 use crate::{ConfigFileInfo, ThemeModule};
 use async_trait::async_trait;
 use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
@@ -81,6 +82,381 @@ async fn find_nvim_sockets() -> Vec<PathBuf> {
     sockets
 }
 
+/// Profile colorscheme name that opts into the generated colorscheme.
+const GENERATED_COLORSCHEME: &str = "lmtt";
+
+/// One highlight group's `nvim_set_hl` spec.
+#[derive(Default)]
+struct Hl {
+    fg: Option<String>,
+    bg: Option<String>,
+    sp: Option<String>,
+    attrs: Vec<&'static str>,
+    link: Option<&'static str>,
+}
+
+impl Hl {
+    fn fg(color: &str) -> Self {
+        Self {
+            fg: Some(color.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn bg(color: &str) -> Self {
+        Self {
+            bg: Some(color.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn fg_bg(fg: &str, bg: &str) -> Self {
+        Self {
+            fg: Some(fg.to_string()),
+            bg: Some(bg.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn link(group: &'static str) -> Self {
+        Self {
+            link: Some(group),
+            ..Self::default()
+        }
+    }
+
+    /// Undercurl in `color`, for diagnostics and spelling.
+    fn curl(color: &str) -> Self {
+        Self {
+            sp: Some(color.to_string()),
+            attrs: vec!["undercurl"],
+            ..Self::default()
+        }
+    }
+
+    fn with(mut self, attr: &'static str) -> Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    fn on(mut self, bg: &str) -> Self {
+        self.bg = Some(bg.to_string());
+        self
+    }
+
+    fn to_lua(&self) -> String {
+        if let Some(link) = self.link {
+            return format!("{{ link = \"{}\" }}", link);
+        }
+        let mut parts = Vec::new();
+        for (key, value) in [("fg", &self.fg), ("bg", &self.bg), ("sp", &self.sp)] {
+            if let Some(value) = value {
+                parts.push(format!("{} = \"{}\"", key, value));
+            }
+        }
+        for attr in &self.attrs {
+            parts.push(format!("{} = true", attr));
+        }
+        format!("{{ {} }}", parts.join(", "))
+    }
+}
+
+/// A complete colorscheme (`colors/lmtt.lua`): editor UI, legacy syntax
+/// groups, treesitter captures, LSP diagnostics and the common plugins.
+/// Plugins that define their own groups with `default = true` pick these up;
+/// anything not listed falls back to the groups it links to.
+fn colorscheme_lua(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let diag = terminal::diagnostics(scheme);
+
+    let surface = c("surface");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let container_low = c("surface_container_low");
+    let container = c("surface_container");
+    let container_high = c("surface_container_high");
+    let container_highest = c("surface_container_highest");
+    let primary = c("primary");
+    let on_primary = c("on_primary");
+    let primary_container = c("primary_container");
+    let on_primary_container = c("on_primary_container");
+    let secondary = c("secondary");
+    let secondary_container = c("secondary_container");
+    let on_secondary_container = c("on_secondary_container");
+    let tertiary = c("tertiary");
+    let tertiary_container = c("tertiary_container");
+    let on_tertiary_container = c("on_tertiary_container");
+    let error_container = c("error_container");
+    let on_error_container = c("on_error_container");
+    let outline = c("outline");
+    let outline_variant = c("outline_variant");
+    let green = term.ansi[2].as_str();
+    let yellow = term.ansi[3].as_str();
+    let cyan = term.ansi[6].as_str();
+    let (error, warning, info, hint) = (
+        diag.error.as_str(),
+        diag.warning.as_str(),
+        diag.info.as_str(),
+        diag.hint.as_str(),
+    );
+    // Diff backgrounds: the hue washed most of the way into the background
+    let wash = |color: &str| {
+        lmtt_core::colors::mix_oklab(color, &surface, 0.8).unwrap_or_else(|_| surface.clone())
+    };
+
+    let groups: Vec<(&str, Hl)> = vec![
+        // Editor UI
+        ("Normal", Hl::fg_bg(&on_surface, &surface)),
+        ("NormalNC", Hl::fg_bg(&on_surface, &surface)),
+        ("NormalFloat", Hl::fg_bg(&on_surface, &container)),
+        ("FloatBorder", Hl::fg_bg(&outline, &container)),
+        ("FloatTitle", Hl::fg_bg(&primary, &container).with("bold")),
+        ("Cursor", Hl::fg_bg(&on_primary, &primary)),
+        ("lCursor", Hl::link("Cursor")),
+        ("CursorIM", Hl::link("Cursor")),
+        ("TermCursor", Hl::link("Cursor")),
+        ("CursorLine", Hl::bg(&container_low)),
+        ("CursorColumn", Hl::bg(&container_low)),
+        ("ColorColumn", Hl::bg(&container_low)),
+        ("CursorLineNr", Hl::fg(&primary).with("bold")),
+        ("LineNr", Hl::fg(&outline)),
+        ("SignColumn", Hl::bg(&surface)),
+        ("FoldColumn", Hl::fg_bg(&outline, &surface)),
+        ("Folded", Hl::fg_bg(&on_surface_variant, &container)),
+        ("WinSeparator", Hl::fg(&outline_variant)),
+        ("VertSplit", Hl::link("WinSeparator")),
+        ("StatusLine", Hl::fg_bg(&on_surface, &container)),
+        (
+            "StatusLineNC",
+            Hl::fg_bg(&on_surface_variant, &container_low),
+        ),
+        ("TabLine", Hl::fg_bg(&on_surface_variant, &container)),
+        ("TabLineFill", Hl::bg(&container_low)),
+        ("TabLineSel", Hl::fg_bg(&on_primary, &primary).with("bold")),
+        ("WinBar", Hl::fg(&on_surface).with("bold")),
+        ("WinBarNC", Hl::fg(&on_surface_variant)),
+        ("Pmenu", Hl::fg_bg(&on_surface, &container)),
+        (
+            "PmenuSel",
+            Hl::fg_bg(&on_primary_container, &primary_container),
+        ),
+        ("PmenuSbar", Hl::bg(&container_high)),
+        ("PmenuThumb", Hl::bg(&outline)),
+        ("WildMenu", Hl::link("PmenuSel")),
+        ("Visual", Hl::bg(&container_highest)),
+        ("VisualNOS", Hl::link("Visual")),
+        (
+            "Search",
+            Hl::fg_bg(&on_tertiary_container, &tertiary_container),
+        ),
+        ("IncSearch", Hl::fg_bg(&on_primary, &primary)),
+        ("CurSearch", Hl::link("IncSearch")),
+        (
+            "Substitute",
+            Hl::fg_bg(&on_error_container, &error_container),
+        ),
+        (
+            "MatchParen",
+            Hl::fg(&primary).on(&container_highest).with("bold"),
+        ),
+        ("NonText", Hl::fg(&outline_variant)),
+        ("Whitespace", Hl::fg(&outline_variant)),
+        ("EndOfBuffer", Hl::fg(&surface)),
+        ("SpecialKey", Hl::fg(&outline)),
+        ("Conceal", Hl::fg(&outline)),
+        ("Directory", Hl::fg(&primary)),
+        ("Title", Hl::fg(&primary).with("bold")),
+        ("ErrorMsg", Hl::fg(error).with("bold")),
+        ("WarningMsg", Hl::fg(warning)),
+        ("MoreMsg", Hl::fg(&primary)),
+        ("ModeMsg", Hl::fg(&on_surface).with("bold")),
+        ("Question", Hl::fg(&primary)),
+        ("QuickFixLine", Hl::bg(&container_high).with("bold")),
+        ("SpellBad", Hl::curl(error)),
+        ("SpellCap", Hl::curl(warning)),
+        ("SpellRare", Hl::curl(hint)),
+        ("SpellLocal", Hl::curl(info)),
+        ("DiffAdd", Hl::bg(&wash(green))),
+        ("DiffChange", Hl::bg(&wash(yellow))),
+        ("DiffDelete", Hl::fg(error).on(&wash(error))),
+        ("DiffText", Hl::bg(&container_highest).with("bold")),
+        ("Added", Hl::fg(green)),
+        ("Changed", Hl::fg(yellow)),
+        ("Removed", Hl::fg(error)),
+        // Legacy syntax groups
+        ("Comment", Hl::fg(&outline).with("italic")),
+        ("Constant", Hl::fg(cyan)),
+        ("String", Hl::fg(green)),
+        ("Character", Hl::link("String")),
+        ("Number", Hl::fg(cyan)),
+        ("Boolean", Hl::fg(cyan)),
+        ("Float", Hl::link("Number")),
+        ("Identifier", Hl::fg(&on_surface)),
+        ("Function", Hl::fg(&tertiary)),
+        ("Statement", Hl::fg(&primary)),
+        ("Conditional", Hl::fg(&primary)),
+        ("Repeat", Hl::fg(&primary)),
+        ("Label", Hl::fg(&primary)),
+        ("Operator", Hl::fg(&on_surface_variant)),
+        ("Keyword", Hl::fg(&primary)),
+        ("Exception", Hl::fg(&primary)),
+        ("PreProc", Hl::fg(&secondary)),
+        ("Include", Hl::fg(&primary)),
+        ("Define", Hl::fg(&secondary)),
+        ("Macro", Hl::fg(&secondary)),
+        ("Type", Hl::fg(&secondary)),
+        ("StorageClass", Hl::fg(&primary)),
+        ("Structure", Hl::fg(&secondary)),
+        ("Typedef", Hl::fg(&secondary)),
+        ("Special", Hl::fg(&tertiary)),
+        ("SpecialChar", Hl::fg(cyan)),
+        ("Tag", Hl::fg(&primary)),
+        ("Delimiter", Hl::fg(&on_surface_variant)),
+        ("SpecialComment", Hl::fg(&on_surface_variant).with("italic")),
+        ("Underlined", Hl::fg(&tertiary).with("underline")),
+        ("Todo", Hl::fg_bg(&on_primary, &primary).with("bold")),
+        ("Error", Hl::fg(error)),
+        // Treesitter
+        ("@variable", Hl::fg(&on_surface)),
+        ("@variable.builtin", Hl::fg(&secondary)),
+        ("@variable.parameter", Hl::fg(&on_surface_variant)),
+        ("@variable.member", Hl::fg(&on_surface)),
+        ("@property", Hl::fg(&on_surface)),
+        ("@constant", Hl::link("Constant")),
+        ("@constant.builtin", Hl::fg(cyan)),
+        ("@module", Hl::fg(&secondary)),
+        ("@label", Hl::link("Label")),
+        ("@string", Hl::link("String")),
+        ("@string.escape", Hl::fg(cyan)),
+        ("@string.special", Hl::fg(cyan)),
+        ("@string.regexp", Hl::fg(cyan)),
+        ("@character", Hl::link("Character")),
+        ("@number", Hl::link("Number")),
+        ("@boolean", Hl::link("Boolean")),
+        ("@type", Hl::link("Type")),
+        ("@type.builtin", Hl::fg(&secondary).with("italic")),
+        ("@attribute", Hl::fg(&tertiary)),
+        ("@function", Hl::link("Function")),
+        ("@function.builtin", Hl::fg(&tertiary).with("italic")),
+        ("@function.macro", Hl::link("Macro")),
+        ("@constructor", Hl::fg(&secondary)),
+        ("@operator", Hl::link("Operator")),
+        ("@keyword", Hl::link("Keyword")),
+        ("@keyword.return", Hl::fg(&primary).with("bold")),
+        ("@punctuation", Hl::link("Delimiter")),
+        ("@comment", Hl::link("Comment")),
+        ("@tag", Hl::link("Tag")),
+        ("@tag.attribute", Hl::fg(&tertiary)),
+        ("@tag.delimiter", Hl::link("Delimiter")),
+        ("@markup.heading", Hl::fg(&primary).with("bold")),
+        ("@markup.strong", Hl::fg(&on_surface).with("bold")),
+        ("@markup.italic", Hl::fg(&on_surface).with("italic")),
+        (
+            "@markup.strikethrough",
+            Hl::fg(&outline).with("strikethrough"),
+        ),
+        ("@markup.link", Hl::fg(&primary)),
+        ("@markup.link.url", Hl::fg(&tertiary).with("underline")),
+        ("@markup.raw", Hl::fg(green)),
+        ("@markup.quote", Hl::fg(&on_surface_variant).with("italic")),
+        ("@markup.list", Hl::fg(&secondary)),
+        ("@diff.plus", Hl::link("Added")),
+        ("@diff.minus", Hl::link("Removed")),
+        ("@diff.delta", Hl::link("Changed")),
+        // LSP
+        ("DiagnosticError", Hl::fg(error)),
+        ("DiagnosticWarn", Hl::fg(warning)),
+        ("DiagnosticInfo", Hl::fg(info)),
+        ("DiagnosticHint", Hl::fg(hint)),
+        ("DiagnosticOk", Hl::fg(green)),
+        ("DiagnosticUnderlineError", Hl::curl(error)),
+        ("DiagnosticUnderlineWarn", Hl::curl(warning)),
+        ("DiagnosticUnderlineInfo", Hl::curl(info)),
+        ("DiagnosticUnderlineHint", Hl::curl(hint)),
+        ("DiagnosticVirtualTextError", Hl::fg(error).on(&wash(error))),
+        (
+            "DiagnosticVirtualTextWarn",
+            Hl::fg(warning).on(&wash(warning)),
+        ),
+        ("DiagnosticVirtualTextInfo", Hl::fg(info).on(&wash(info))),
+        ("DiagnosticVirtualTextHint", Hl::fg(hint).on(&wash(hint))),
+        ("DiagnosticUnnecessary", Hl::fg(&outline)),
+        (
+            "DiagnosticDeprecated",
+            Hl::fg(&outline).with("strikethrough"),
+        ),
+        ("LspReferenceText", Hl::bg(&container_high)),
+        ("LspReferenceRead", Hl::bg(&container_high)),
+        ("LspReferenceWrite", Hl::bg(&container_highest)),
+        ("LspInlayHint", Hl::fg(&outline).with("italic")),
+        ("LspSignatureActiveParameter", Hl::fg(&primary).with("bold")),
+        ("LspCodeLens", Hl::fg(&outline)),
+        // Plugins: gitsigns, telescope, nvim-tree/neo-tree, cmp, blink,
+        // which-key, indent-blankline, lazy
+        ("GitSignsAdd", Hl::fg(green)),
+        ("GitSignsChange", Hl::fg(yellow)),
+        ("GitSignsDelete", Hl::fg(error)),
+        ("TelescopeNormal", Hl::link("NormalFloat")),
+        ("TelescopeBorder", Hl::link("FloatBorder")),
+        ("TelescopeTitle", Hl::link("FloatTitle")),
+        ("TelescopeSelection", Hl::link("PmenuSel")),
+        ("TelescopeMatching", Hl::fg(&primary).with("bold")),
+        ("TelescopePromptPrefix", Hl::fg(&primary)),
+        ("NvimTreeNormal", Hl::fg_bg(&on_surface, &container_low)),
+        ("NvimTreeFolderIcon", Hl::fg(&primary)),
+        ("NvimTreeRootFolder", Hl::fg(&primary).with("bold")),
+        ("NeoTreeNormal", Hl::fg_bg(&on_surface, &container_low)),
+        ("NeoTreeNormalNC", Hl::fg_bg(&on_surface, &container_low)),
+        ("NeoTreeDirectoryIcon", Hl::fg(&primary)),
+        ("NeoTreeRootName", Hl::fg(&primary).with("bold")),
+        ("CmpItemAbbrMatch", Hl::fg(&primary).with("bold")),
+        ("CmpItemAbbrMatchFuzzy", Hl::fg(&primary)),
+        (
+            "CmpItemAbbrDeprecated",
+            Hl::fg(&outline).with("strikethrough"),
+        ),
+        ("CmpItemKind", Hl::fg(&secondary)),
+        ("CmpItemMenu", Hl::fg(&outline)),
+        ("BlinkCmpLabelMatch", Hl::fg(&primary).with("bold")),
+        ("BlinkCmpKind", Hl::fg(&secondary)),
+        ("WhichKey", Hl::fg(&primary)),
+        ("WhichKeyGroup", Hl::fg(&secondary)),
+        ("WhichKeyDesc", Hl::fg(&on_surface)),
+        ("WhichKeySeparator", Hl::fg(&outline)),
+        ("IblIndent", Hl::fg(&outline_variant)),
+        ("IblScope", Hl::fg(&outline)),
+        ("LazyNormal", Hl::link("NormalFloat")),
+        (
+            "LazyButtonActive",
+            Hl::fg_bg(&on_secondary_container, &secondary_container),
+        ),
+    ];
+
+    let mut lua = String::new();
+    lua.push_str("-- Neovim colorscheme generated by lmtt\n");
+    lua.push_str(&format!("-- Mode: {}\n\n", scheme.mode));
+    lua.push_str("vim.cmd(\"highlight clear\")\n");
+    lua.push_str("if vim.fn.exists(\"syntax_on\") == 1 then\n  vim.cmd(\"syntax reset\")\nend\n");
+    lua.push_str(&format!("vim.o.background = \"{}\"\n", scheme.mode));
+    lua.push_str("vim.o.termguicolors = true\n");
+    lua.push_str(&format!(
+        "vim.g.colors_name = \"{}\"\n\n",
+        GENERATED_COLORSCHEME
+    ));
+    lua.push_str(
+        "local hl = function(group, spec)\n  vim.api.nvim_set_hl(0, group, spec)\nend\n\n",
+    );
+    for (group, spec) in groups {
+        lua.push_str(&format!("hl(\"{}\", {})\n", group, spec.to_lua()));
+    }
+    lua.push('\n');
+    for (i, color) in term.ansi.iter().chain(term.brights.iter()).enumerate() {
+        lua.push_str(&format!("vim.g.terminal_color_{} = \"{}\"\n", i, color));
+    }
+    lua
+}
+
 #[async_trait]
 impl ThemeModule for NvimModule {
     fn name(&self) -> &'static str {
@@ -123,14 +499,35 @@ impl ThemeModule for NvimModule {
             );
         }
 
+        // The opt-in generated colorscheme: write it, then just load it. No
+        // user-side Lua hook is involved.
+        let generated = colorscheme == Some(GENERATED_COLORSCHEME);
+        if generated {
+            let colors_dir = dirs::config_dir()
+                .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+                .join("nvim")
+                .join("colors");
+            tokio::fs::create_dir_all(&colors_dir).await?;
+            let colors_file = colors_dir.join("lmtt.lua");
+            lmtt_core::fsutil::write_atomic(&colors_file, colorscheme_lua(scheme)).await?;
+            tracing::info!("[Nvim] Updated colorscheme at {}", colors_file.display());
+        }
+
         // --remote-expr evaluates VIMSCRIPT: a global Lua function is reached
         // via v:lua, not _G (which is Lua syntax and always errors with E121).
-        let expr = if let Some(cs) = colorscheme {
+        let expr = if generated {
+            format!("execute('colorscheme {}')", GENERATED_COLORSCHEME)
+        } else if let Some(cs) = colorscheme {
             format!("v:lua.set_nvim_theme('{}', '{}')", mode, cs)
         } else {
             format!("v:lua.set_nvim_theme('{}')", mode)
         };
 
+        if !crate::process_running("nvim").await {
+            tracing::debug!("[Nvim] No running instances found");
+            return Ok(());
+        }
+
         let mut updated = 0;
         let mut failed = 0;
 
@@ -174,7 +571,9 @@ impl ThemeModule for NvimModule {
         if updated > 0 {
             tracing::info!("[Nvim] Updated {} instance(s)", updated);
         }
-        if failed > 0 {
+        if failed > 0 && generated {
+            tracing::warn!("[Nvim] {} instance(s) not updated", failed);
+        } else if failed > 0 {
             tracing::warn!(
                 "[Nvim] {} instance(s) not updated — is a global set_nvim_theme(mode, colorscheme?) Lua function defined?",
                 failed
@@ -191,3 +590,23 @@ impl ThemeModule for NvimModule {
         Ok(vec![])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::colorscheme_lua;
+    use lmtt_core::{ColorScheme, ThemeMode};
+
+    #[test]
+    fn colorscheme_sets_background_name_and_groups() {
+        let mut scheme = ColorScheme::new(ThemeMode::Light);
+        scheme.set("warning".into(), "#ff8800".into());
+        let lua = colorscheme_lua(&scheme);
+        assert!(lua.contains("vim.o.background = \"light\"\n"));
+        assert!(lua.contains("vim.g.colors_name = \"lmtt\"\n"));
+        assert!(lua.contains("hl(\"Normal\", { fg = \""));
+        assert!(lua.contains("hl(\"@string\", { link = \"String\" })\n"));
+        // A custom warning token reaches the diagnostics
+        assert!(lua.contains("hl(\"DiagnosticWarn\", { fg = \"#ff8800\" })\n"));
+        assert!(lua.contains("vim.g.terminal_color_15 = "));
+    }
+}
@@ -101,7 +101,9 @@ async fn platform_theme_is_qt6ct() -> bool {
     session_env_has_platformtheme_value("qt6ct").await
 }
 
-async fn session_env_has_any_platformtheme() -> bool {
+/// Whether the systemd user environment (what D-Bus-activated and
+/// systemd-launched apps inherit) sets QT_QPA_PLATFORMTHEME at all.
+pub async fn session_env_has_any_platformtheme() -> bool {
     let Ok(output) = tokio::process::Command::new("systemctl")
         .args(["--user", "show-environment"])
         .output()
@@ -188,7 +190,7 @@ impl ThemeModule for QtModule {
                 }
             }
 
-            if which::which("hyprctl").is_ok() {
+            if crate::process_running("Hyprland").await {
                 let _ = tokio::process::Command::new("hyprctl")
                     .args(["setenv", "QT_QPA_PLATFORMTHEME", "qt6ct"])
                     .output()
@@ -4,6 +4,19 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Target spacing between transition frames (~20 fps). Each frame is a few
+/// process spawns or tty writes per module, so faster buys nothing visible.
+const FRAME_INTERVAL_MS: u64 = 50;
+
+/// Upper bound on frames however long the transition, so a typo'd
+/// transition_ms can't turn a switch into minutes of process spawning.
+const MAX_FRAMES: u64 = 120;
+
+/// How long one module may take over one frame before it drops out of the
+/// fade. A few frame intervals, not performance.timeout: a module stuck for
+/// seconds would otherwise hold up every frame after it.
+const FRAME_TIMEOUT_MS: u64 = 4 * FRAME_INTERVAL_MS;
+
 pub struct ModuleRegistry {
     pub modules: Vec<Arc<dyn ThemeModule>>,
 }
@@ -140,6 +153,66 @@ impl ModuleRegistry {
         results
     }
 
+    /// Fade every enabled live-recolor module from `from` to `to` over
+    /// `switch.transition_ms`, interpolating in Oklab. Pushes only the
+    /// intermediate frames — call `apply_all` afterwards for the final state.
+    /// A module whose frame fails or overruns drops out of the rest of the
+    /// fade; the switch itself is never failed by a transition.
+    pub async fn transition(&self, from: &ColorScheme, to: &ColorScheme, config: &Config) {
+        let duration_ms = config.switch.transition_ms;
+        if duration_ms == 0 {
+            return;
+        }
+        let mut live: Vec<Arc<dyn ThemeModule>> = self
+            .modules
+            .iter()
+            .filter(|m| m.supports_live_recolor() && m.is_enabled(config))
+            .cloned()
+            .collect();
+        if live.is_empty() {
+            return;
+        }
+
+        let frames = (duration_ms / FRAME_INTERVAL_MS).clamp(2, MAX_FRAMES);
+        let interval = Duration::from_millis(duration_ms / frames);
+        let budget = Duration::from_millis(FRAME_TIMEOUT_MS);
+        tracing::debug!(
+            "[Registry] Transition: {} frames over {}ms to {} module(s)",
+            frames,
+            duration_ms,
+            live.len()
+        );
+
+        let start = tokio::time::Instant::now();
+        for i in 1..frames {
+            let frame = from.interpolate(to, i as f32 / frames as f32);
+
+            let mut tasks = tokio::task::JoinSet::new();
+            for module in &live {
+                let module = Arc::clone(module);
+                let frame = frame.clone();
+                tasks.spawn(async move {
+                    let ok = matches!(
+                        tokio::time::timeout(budget, module.apply_frame(&frame)).await,
+                        Ok(Ok(()))
+                    );
+                    (module.name(), ok)
+                });
+            }
+            while let Some(joined) = tasks.join_next().await {
+                if let Ok((name, false)) = joined {
+                    tracing::debug!("[Registry] {} dropped out of the transition", name);
+                    live.retain(|m| m.name() != name);
+                }
+            }
+            if live.is_empty() {
+                return;
+            }
+
+            tokio::time::sleep_until(start + interval * i as u32).await;
+        }
+    }
+
     /// Get list of all enabled module names
     pub fn enabled_modules(&self, config: &Config) -> Vec<&str> {
         self.modules
@@ -199,3 +272,74 @@ impl ModuleResult {
         self.duration_ms > threshold_ms
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct LiveModule {
+        name: &'static str,
+        frames: Arc<AtomicUsize>,
+        stuck: bool,
+    }
+
+    #[async_trait]
+    impl ThemeModule for LiveModule {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+        fn binary_name(&self) -> &'static str {
+            self.name
+        }
+        fn is_installed(&self) -> bool {
+            true
+        }
+        fn supports_live_recolor(&self) -> bool {
+            true
+        }
+        async fn apply(&self, _s: &ColorScheme, _c: &Config) -> Result<()> {
+            Ok(())
+        }
+        async fn apply_frame(&self, _scheme: &ColorScheme) -> Result<()> {
+            self.frames.fetch_add(1, Ordering::SeqCst);
+            if self.stuck {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn transition_sends_intermediate_frames_and_drops_stuck_modules() {
+        let ok = Arc::new(AtomicUsize::new(0));
+        let stuck = Arc::new(AtomicUsize::new(0));
+        let registry = ModuleRegistry {
+            modules: vec![
+                Arc::new(LiveModule {
+                    name: "live",
+                    frames: Arc::clone(&ok),
+                    stuck: false,
+                }),
+                Arc::new(LiveModule {
+                    name: "stuck",
+                    frames: Arc::clone(&stuck),
+                    stuck: true,
+                }),
+            ],
+        };
+        let mut config = Config::default();
+        config.switch.transition_ms = 10 * FRAME_INTERVAL_MS;
+        let from = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        let to = ColorScheme::new(lmtt_core::ThemeMode::Light);
+
+        let start = Instant::now();
+        registry.transition(&from, &to, &config).await;
+
+        // 10 frames, the last of which is left to apply_all
+        assert_eq!(ok.load(Ordering::SeqCst), 9);
+        assert_eq!(stuck.load(Ordering::SeqCst), 1);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
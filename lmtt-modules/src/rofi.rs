@@ -0,0 +1,104 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(RofiModule);
+
+pub struct RofiModule;
+
+impl Default for RofiModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RofiModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const INCLUDE_LINE: &str = "@import \"lmtt-colors.rasi\"";
+
+fn rofi_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("rofi"))
+}
+
+/// Every token as a rasi variable on `*`, for themes to reference as
+/// `@primary`, `@on-surface`, ... Rasi property names can't contain `_`,
+/// so token names are kebab-cased.
+fn colors_rasi(scheme: &ColorScheme) -> String {
+    let mut content = String::new();
+    content.push_str("/* Rofi colors generated by lmtt */\n");
+    content.push_str(&format!("/* Mode: {} */\n\n", scheme.mode));
+    content.push_str("* {\n");
+    for (key, value) in crate::sorted_colors(scheme) {
+        content.push_str(&format!("    {}: {};\n", key.replace('_', "-"), value));
+    }
+    content.push_str("}\n");
+    content
+}
+
+#[async_trait]
+impl ThemeModule for RofiModule {
+    fn name(&self) -> &'static str {
+        "rofi"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "rofi"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = rofi_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("lmtt-colors.rasi");
+        lmtt_core::fsutil::write_atomic(&colors_file, colors_rasi(scheme)).await?;
+
+        // Rofi reads its theme on every launch; there is nothing to reload.
+        tracing::info!("[Rofi] Updated colors at {}", colors_file.display());
+
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config_rasi = rofi_dir()?.join("config.rasi");
+        if !config_rasi.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config_rasi).await?;
+        let already_included = crate::is_included(&content, INCLUDE_LINE);
+
+        Ok(vec![ConfigFileInfo {
+            path: config_rasi,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Import lmtt colors into rofi config.rasi".to_string(),
+            already_included,
+        }])
+    }
+
+    /// An `@theme` directive discards everything parsed before it, so an
+    /// import at the top of config.rasi would be lost whenever the user
+    /// loads a theme.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_kebab_case_variables() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("on_surface".into(), "#e0e2e8".into());
+        scheme.set("primary".into(), "#9ccaff".into());
+        let rasi = colors_rasi(&scheme);
+        assert!(rasi.contains("* {\n    on-surface: #e0e2e8;\n    primary: #9ccaff;\n}\n"));
+    }
+}
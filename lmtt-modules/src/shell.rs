@@ -0,0 +1,89 @@
+use lmtt_core::{ColorScheme, Result};
+use std::path::{Path, PathBuf};
+
+/// Where the zsh, bash and LS_COLORS files live. rc files source them from
+/// here, and a prompt hook re-sources them after a switch.
+pub(crate) fn shell_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("lmtt")
+        .join("shell"))
+}
+
+/// Single-quote for sh, bash and zsh.
+pub(crate) fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub(crate) fn path_quote(path: &Path) -> String {
+    sh_quote(&path.to_string_lossy())
+}
+
+/// The header and `LMTT_*` exports (every token, plus the mode), followed by
+/// the LS_COLORS file when the dircolors module has written one, so the
+/// prompt hook refreshes it too.
+pub(crate) fn exports(scheme: &ColorScheme, name: &str) -> Result<String> {
+    let mut content = String::new();
+    content.push_str(&format!("# {} colors generated by lmtt\n", name));
+    content.push_str(&format!("# Mode: {}\n\n", scheme.mode));
+    content.push_str(&format!("export LMTT_MODE={}\n", scheme.mode));
+    for (key, value) in crate::sorted_colors(scheme) {
+        content.push_str(&format!(
+            "export LMTT_{}={}\n",
+            key.to_uppercase(),
+            sh_quote(value)
+        ));
+    }
+
+    let ls_colors = path_quote(&shell_dir()?.join("ls-colors.sh"));
+    content.push_str(&format!("\n[ -r {0} ] && . {0}\n", ls_colors));
+    Ok(content)
+}
+
+/// `FZF_DEFAULT_OPTS` with lmtt's `--color` after the user's own options.
+/// Those are captured the first time the file is sourced, so re-sourcing
+/// replaces the colors instead of stacking them.
+pub(crate) fn fzf_opts(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let colors = [
+        ("fg", c("on_surface")),
+        ("bg", "-1".to_string()),
+        ("hl", c("primary")),
+        ("fg+", c("on_surface")),
+        ("bg+", c("surface_container_high")),
+        ("hl+", c("primary")),
+        ("info", c("secondary")),
+        ("prompt", c("primary")),
+        ("pointer", c("tertiary")),
+        ("marker", c("tertiary")),
+        ("spinner", c("tertiary")),
+        ("header", c("outline")),
+        ("border", c("outline_variant")),
+        ("gutter", "-1".to_string()),
+    ]
+    .iter()
+    .map(|(slot, color)| format!("{}:{}", slot, color))
+    .collect::<Vec<_>>()
+    .join(",");
+
+    format!(
+        ": \"${{LMTT_FZF_USER_OPTS=${{FZF_DEFAULT_OPTS-}}}}\"\n\
+         export FZF_DEFAULT_OPTS=\"${{LMTT_FZF_USER_OPTS:+$LMTT_FZF_USER_OPTS }}--color={}\"\n",
+        colors
+    )
+}
+
+/// Starts the first line of a generated colors file, followed by its stamp.
+pub(crate) const STAMP_PREFIX: &str = "# lmtt-stamp: ";
+
+/// A hash of a colors file's body. The file carries it twice: in its first
+/// line, which the prompt hook reads with the `read` builtin (no fork per
+/// prompt), and in `_lmtt_stamp`, set when the file is sourced. The hook
+/// re-sources whenever the two differ, so every change is picked up however
+/// close together switches come, and identical colors never re-source.
+pub(crate) fn stamp(body: &str) -> String {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
@@ -0,0 +1,233 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+use toml_edit::{DocumentMut, Item, Table};
+
+crate::register_module!(StarshipModule);
+
+pub struct StarshipModule;
+
+impl Default for StarshipModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StarshipModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const PALETTE: &str = "lmtt";
+
+/// `$STARSHIP_CONFIG`, else `~/.config/starship.toml`, as starship itself
+/// looks it up.
+fn starship_toml() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os("STARSHIP_CONFIG").filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("starship.toml"))
+}
+
+fn parse_config(content: &str) -> Result<DocumentMut> {
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| lmtt_core::Error::Module(format!("starship.toml does not parse: {}", e)))
+}
+
+fn palette_is_lmtt(content: &str) -> bool {
+    content
+        .parse::<DocumentMut>()
+        .ok()
+        .and_then(|doc| {
+            doc.get("palette")
+                .and_then(Item::as_str)
+                .map(|p| p == PALETTE)
+        })
+        .unwrap_or(false)
+}
+
+/// Rewrite `[palettes.lmtt]` with every token as a named color. An existing
+/// table keeps its place and comments; a new one goes at the end.
+fn set_palette_table(content: &str, scheme: &ColorScheme) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    let palettes = doc
+        .entry("palettes")
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| {
+            lmtt_core::Error::Module("starship.toml: `palettes` is not a table".into())
+        })?;
+    let palette = palettes
+        .entry(PALETTE)
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| {
+            lmtt_core::Error::Module("starship.toml: `palettes.lmtt` is not a table".into())
+        })?;
+
+    palette.clear();
+    for (key, value) in crate::sorted_colors(scheme) {
+        palette.insert(key, toml_edit::value(value.as_str()));
+    }
+    Ok(doc.to_string())
+}
+
+/// Set the root `palette` key in place (keeping its comments) or add it
+/// ahead of the first table, like any other root key.
+fn set_palette(content: &str) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    match doc.get_mut("palette").and_then(Item::as_value_mut) {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = PALETTE.into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            doc.insert("palette", toml_edit::value(PALETTE));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Drop `palette = "lmtt"` and `[palettes.lmtt]`. A palette the user picked
+/// since is left selected.
+fn unset_palette(content: &str) -> Result<String> {
+    let mut doc = parse_config(content)?;
+    let mut changed = false;
+    if palette_is_lmtt(content) {
+        doc.remove("palette");
+        changed = true;
+    }
+    if let Some(palettes) = doc.get_mut("palettes").and_then(Item::as_table_mut) {
+        changed |= palettes.remove(PALETTE).is_some();
+        if palettes.is_empty() {
+            doc.remove("palettes");
+        }
+    }
+    if !changed {
+        return Ok(content.to_string());
+    }
+    Ok(doc.to_string())
+}
+
+#[async_trait]
+impl ThemeModule for StarshipModule {
+    fn name(&self) -> &'static str {
+        "starship"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "starship"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let config = starship_toml()?;
+        if !config.exists() {
+            tracing::debug!("[Starship] No starship.toml; nothing to color");
+            return Ok(());
+        }
+
+        let content = tokio::fs::read_to_string(&config).await?;
+        let updated = set_palette_table(&content, scheme)?;
+        if updated != content {
+            lmtt_core::fsutil::write_atomic(&config, updated).await?;
+        }
+
+        // The prompt re-reads starship.toml on every render.
+        tracing::info!("[Starship] Updated palette in {}", config.display());
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config = starship_toml()?;
+        if !config.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config).await?;
+        let already_included = palette_is_lmtt(&content);
+
+        Ok(vec![ConfigFileInfo {
+            path: config,
+            include_line: "palette = \"lmtt\"".to_string(),
+            description: "Use the LMTT palette in starship.toml".to_string(),
+            already_included,
+        }])
+    }
+
+    /// `palette` is a root key and `[palettes.lmtt]` a table: both are
+    /// edited in place, since a marker block would repeat the table (invalid
+    /// TOML) or land inside whatever table precedes it.
+    async fn inject_config(&self, config_file: &ConfigFileInfo) -> Result<()> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Err(lmtt_core::Error::Module(format!(
+                "Config file not found: {}",
+                path.display()
+            )));
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = set_palette(&content)?;
+        if new_content != content {
+            lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_config(&self, config_file: &ConfigFileInfo) -> Result<bool> {
+        let path = &config_file.path;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let new_content = unset_palette(&content)?;
+        if new_content == content {
+            return Ok(false);
+        }
+        lmtt_core::fsutil::write_atomic(path, new_content).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_table_is_rewritten_in_place() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#9ccaff".into());
+        scheme.set("error".into(), "#ffb4ab".into());
+        let conf = "format = \"$all\"\n\n[palettes.lmtt]\nprimary = \"#000000\"\nstale = \"#111111\"\n\n[git_branch]\nstyle = \"bold primary\"\n";
+        let updated = set_palette_table(conf, &scheme).unwrap();
+        assert_eq!(
+            updated,
+            "format = \"$all\"\n\n[palettes.lmtt]\nerror = \"#ffb4ab\"\nprimary = \"#9ccaff\"\n\n[git_branch]\nstyle = \"bold primary\"\n"
+        );
+        assert_eq!(set_palette_table(&updated, &scheme).unwrap(), updated);
+    }
+
+    #[test]
+    fn palette_key_is_set_and_removed() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Light);
+        scheme.set("primary".into(), "#3a5f8a".into());
+        let conf = "# prompt\n[character]\nsuccess_symbol = \"[>](bold green)\"\n";
+        let set = set_palette(&set_palette_table(conf, &scheme).unwrap()).unwrap();
+        assert!(set.starts_with("palette = \"lmtt\"\n"));
+        assert!(set.contains("[palettes.lmtt]\nprimary = \"#3a5f8a\"\n"));
+        assert!(palette_is_lmtt(&set));
+        assert_eq!(unset_palette(&set).unwrap(), conf);
+        assert_eq!(unset_palette(conf).unwrap(), conf);
+    }
+}
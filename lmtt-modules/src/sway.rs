@@ -0,0 +1,325 @@
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+crate::register_module!(SwayModule);
+
+/// Sway and i3: same config syntax, same IPC protocol.
+pub struct SwayModule;
+
+impl Default for SwayModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SwayModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const IPC_MAGIC: &[u8] = b"i3-ipc";
+const IPC_RUN_COMMAND: u32 = 0;
+
+/// Config dirs of the window managers that are set up, sway first.
+fn wm_dirs() -> Result<Vec<PathBuf>> {
+    let config_dir = dirs::config_dir().ok_or(lmtt_core::Error::Config("No config dir".into()))?;
+    Ok(["sway", "i3"]
+        .into_iter()
+        .map(|wm| config_dir.join(wm))
+        .filter(|dir| dir.join("config").exists())
+        .collect())
+}
+
+/// sway/i3 take #rrggbb (or #rrggbbaa); re-emit canonical #rrggbb.
+fn wm_hex(hex: &str) -> String {
+    match lmtt_core::colors::hex_to_rgb(hex) {
+        Ok((r, g, b)) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Err(_) => "#000000".to_string(),
+    }
+}
+
+/// `client.<class> border background text indicator child_border` lines,
+/// shared by the include file and the live IPC update.
+fn client_colors(scheme: &ColorScheme) -> Vec<String> {
+    let get = |key: &str| wm_hex(&scheme.get_or_fallback(key));
+    [
+        (
+            "focused",
+            ["primary", "primary", "on_primary", "tertiary", "primary"],
+        ),
+        (
+            "focused_inactive",
+            [
+                "surface_container_highest",
+                "surface_container_highest",
+                "on_surface",
+                "outline",
+                "surface_container_highest",
+            ],
+        ),
+        (
+            "unfocused",
+            [
+                "surface_container",
+                "surface_container",
+                "on_surface_variant",
+                "outline_variant",
+                "surface_container",
+            ],
+        ),
+        ("urgent", ["error", "error", "on_error", "error", "error"]),
+        (
+            "placeholder",
+            ["surface", "surface", "on_surface", "outline", "surface"],
+        ),
+    ]
+    .into_iter()
+    .map(|(class, tokens)| {
+        let colors: Vec<String> = tokens.into_iter().map(get).collect();
+        format!("client.{} {}", class, colors.join(" "))
+    })
+    .collect()
+}
+
+/// The include file: client colors, plus `$lmtt_bar_*` variables for the
+/// user's own `bar { colors { } }` block. A bar block here would add a
+/// second bar rather than recolor theirs.
+fn colors_config(scheme: &ColorScheme) -> String {
+    let get = |key: &str| wm_hex(&scheme.get_or_fallback(key));
+    let mut content = String::new();
+    content.push_str("# Sway/i3 colors generated by lmtt\n");
+    content.push_str(&format!("# Mode: {}\n", scheme.mode));
+    content.push_str("# Reference the bar variables in your bar block, e.g.\n");
+    content.push_str("#   bar {\n#       colors {\n");
+    content.push_str("#           background $lmtt_bar_background\n");
+    content.push_str("#           statusline $lmtt_bar_statusline\n");
+    content.push_str("#           focused_workspace $lmtt_bar_focused_workspace\n");
+    content.push_str("#       }\n#   }\n\n");
+    for line in client_colors(scheme) {
+        content.push_str(&line);
+        content.push('\n');
+    }
+    content.push('\n');
+    content.push_str(&format!(
+        "set $lmtt_bar_background {}\n",
+        get("surface_container_low")
+    ));
+    content.push_str(&format!("set $lmtt_bar_statusline {}\n", get("on_surface")));
+    content.push_str(&format!(
+        "set $lmtt_bar_separator {}\n",
+        get("outline_variant")
+    ));
+    // Workspace buttons: border background text
+    for (name, border, background, text) in [
+        ("focused_workspace", "primary", "primary", "on_primary"),
+        (
+            "active_workspace",
+            "surface_container_highest",
+            "surface_container_highest",
+            "on_surface",
+        ),
+        (
+            "inactive_workspace",
+            "surface_container_low",
+            "surface_container_low",
+            "on_surface_variant",
+        ),
+        ("urgent_workspace", "error", "error", "on_error"),
+    ] {
+        content.push_str(&format!(
+            "set $lmtt_bar_{} {} {} {}\n",
+            name,
+            get(border),
+            get(background),
+            get(text)
+        ));
+    }
+    content
+}
+
+/// Frame an i3/sway IPC message: magic, payload length and type (both
+/// native-endian u32), payload.
+fn ipc_message(kind: u32, payload: &str) -> Vec<u8> {
+    let mut message = Vec::with_capacity(IPC_MAGIC.len() + 8 + payload.len());
+    message.extend_from_slice(IPC_MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&kind.to_ne_bytes());
+    message.extend_from_slice(payload.as_bytes());
+    message
+}
+
+/// The running WM's IPC socket: $SWAYSOCK, then $I3SOCK, then asking i3.
+async fn ipc_socket() -> Option<PathBuf> {
+    for var in ["SWAYSOCK", "I3SOCK"] {
+        if let Some(path) = std::env::var_os(var).filter(|p| !p.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    let output = tokio::process::Command::new("i3")
+        .arg("--get-socketpath")
+        .output()
+        .await
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Run `;`-separated commands over IPC. Returns the error messages of any
+/// command the WM rejected.
+async fn run_commands(socket: &std::path::Path, commands: &str) -> Result<Vec<String>> {
+    let mut stream = tokio::net::UnixStream::connect(socket).await?;
+    stream
+        .write_all(&ipc_message(IPC_RUN_COMMAND, commands))
+        .await?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).await?;
+    if &header[..6] != IPC_MAGIC {
+        return Err(lmtt_core::Error::Module(
+            "Unexpected reply on the sway/i3 IPC socket".into(),
+        ));
+    }
+    let len = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await?;
+
+    // [{"success": true}, {"success": false, "error": "..."}, ...]
+    let replies: Vec<serde_json::Value> = serde_json::from_slice(&payload)?;
+    Ok(replies
+        .iter()
+        .filter(|reply| reply["success"] != true)
+        .map(|reply| {
+            reply["error"]
+                .as_str()
+                .unwrap_or("unknown error")
+                .to_string()
+        })
+        .collect())
+}
+
+/// Set client colors on the running WM without a `reload`, which would
+/// re-apply outputs and inputs and restart the bars. Returns false when no
+/// sway/i3 is reachable (e.g. a stale $SWAYSOCK from another session).
+async fn apply_live(scheme: &ColorScheme) -> Result<bool> {
+    let Some(socket) = ipc_socket().await else {
+        return Ok(false);
+    };
+    if !socket.exists() {
+        return Ok(false);
+    }
+    let commands = client_colors(scheme).join("; ");
+    let errors = match tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        run_commands(&socket, &commands),
+    )
+    .await
+    {
+        Ok(Ok(errors)) => errors,
+        Ok(Err(e)) => {
+            tracing::debug!("[Sway] IPC at {} unavailable: {}", socket.display(), e);
+            return Ok(false);
+        }
+        Err(_) => {
+            tracing::debug!("[Sway] IPC at {} timed out", socket.display());
+            return Ok(false);
+        }
+    };
+    if !errors.is_empty() {
+        return Err(lmtt_core::Error::Module(format!(
+            "sway/i3 rejected client colors: {}",
+            errors.join("; ")
+        )));
+    }
+    Ok(true)
+}
+
+#[async_trait]
+impl ThemeModule for SwayModule {
+    fn name(&self) -> &'static str {
+        "sway"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "sway"
+    }
+
+    fn is_installed(&self) -> bool {
+        ["sway", "i3"].iter().any(|bin| which::which(bin).is_ok())
+    }
+
+    fn priority(&self) -> u8 {
+        100
+    }
+
+    fn supports_live_recolor(&self) -> bool {
+        true
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let content = colors_config(scheme);
+        for dir in wm_dirs()? {
+            let colors_file = dir.join("lmtt-colors");
+            lmtt_core::fsutil::write_atomic(&colors_file, content.clone()).await?;
+            tracing::info!("[Sway] Updated colors at {}", colors_file.display());
+        }
+
+        if crate::process_running("sway|i3").await && apply_live(scheme).await? {
+            tracing::info!("[Sway] Applied client colors over IPC");
+        } else {
+            tracing::debug!("[Sway] No running sway/i3; colors apply on next start");
+        }
+        Ok(())
+    }
+
+    async fn apply_frame(&self, scheme: &ColorScheme) -> Result<()> {
+        apply_live(scheme).await.map(|_| ())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let mut files = Vec::new();
+        for dir in wm_dirs()? {
+            let config = dir.join("config");
+            // Relative includes resolve against the including file's dir.
+            // At the top, so the user's own client.* lines still win at load
+            // and $lmtt_bar_* is defined before their bar block.
+            let include_line = "include lmtt-colors";
+            let content = tokio::fs::read_to_string(&config).await?;
+            let already_included = crate::is_included(&content, include_line);
+            files.push(ConfigFileInfo {
+                path: config,
+                include_line: include_line.to_string(),
+                description: "Include lmtt colors in sway/i3 config".to_string(),
+                already_included,
+            });
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_lines_have_five_colors() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#abc".into());
+        let lines = client_colors(&scheme);
+        assert!(lines[0].starts_with("client.focused #aabbcc #aabbcc "));
+        assert!(lines.iter().all(|line| line.split(' ').count() == 6));
+        assert!(colors_config(&scheme).contains("set $lmtt_bar_focused_workspace #aabbcc #aabbcc "));
+    }
+
+    #[test]
+    fn ipc_framing() {
+        let message = ipc_message(IPC_RUN_COMMAND, "client.focused #000000");
+        assert_eq!(&message[..6], b"i3-ipc");
+        assert_eq!(u32::from_ne_bytes(message[6..10].try_into().unwrap()), 22);
+        assert_eq!(u32::from_ne_bytes(message[10..14].try_into().unwrap()), 0);
+        assert_eq!(&message[14..], b"client.focused #000000");
+    }
+}
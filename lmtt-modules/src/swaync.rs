@@ -152,13 +152,7 @@ impl ThemeModule for SwayNCModule {
         // dependency while the user runs a different daemon (dunst/hyprpanel);
         // erroring on an unreachable daemon would fail this module — and pop a
         // FAILED notification — on every single switch.
-        let running = tokio::process::Command::new("pgrep")
-            .args(["-x", "swaync"])
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-        if !running {
+        if !crate::process_running("swaync").await {
             tracing::debug!("[SwayNC] Not running; wrote colors, skipping reload");
             return Ok(());
         }
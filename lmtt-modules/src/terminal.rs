@@ -0,0 +1,169 @@
+use lmtt_core::{ColorScheme, ThemeMode};
+
+/// Fixed ANSI hues per mode for the slots Material You has no semantic token
+/// for. Green must be green and cyan must be cyan — `ls`, diffs, and TUIs
+/// depend on the conventional hue of each slot.
+struct AnsiHues {
+    green: &'static str,
+    bright_green: &'static str,
+    yellow: &'static str,
+    bright_yellow: &'static str,
+    cyan: &'static str,
+    bright_cyan: &'static str,
+}
+
+const DARK_HUES: AnsiHues = AnsiHues {
+    green: "#98c379",
+    bright_green: "#b5e890",
+    yellow: "#e5c07b",
+    bright_yellow: "#ecd09b",
+    cyan: "#56b6c2",
+    bright_cyan: "#7bdfec",
+};
+
+const LIGHT_HUES: AnsiHues = AnsiHues {
+    green: "#50a14f",
+    bright_green: "#3f8e3e",
+    yellow: "#c18401",
+    bright_yellow: "#a06d00",
+    cyan: "#0184bc",
+    bright_cyan: "#016d9c",
+};
+
+/// The terminal palette lmtt derives from a scheme. Shared by every terminal
+/// module so they all map the scheme to the 16 ANSI slots the same way; feeds
+/// each terminal's colors file and the OSC payload for running terminals.
+pub(crate) struct TermColors {
+    pub(crate) foreground: String,
+    pub(crate) background: String,
+    pub(crate) cursor_bg: String,
+    pub(crate) cursor_fg: String,
+    pub(crate) selection_bg: String,
+    pub(crate) selection_fg: String,
+    pub(crate) ansi: [String; 8],
+    pub(crate) brights: [String; 8],
+}
+
+pub(crate) fn term_colors(scheme: &ColorScheme) -> TermColors {
+    let is_light = scheme.mode == ThemeMode::Light;
+    let hues = if is_light { &LIGHT_HUES } else { &DARK_HUES };
+
+    let foreground = scheme.get_or_fallback("on_surface");
+    let cursor_bg = scheme.get_or_fallback("primary");
+    let outline = scheme.get_or_fallback("outline");
+    let error = scheme.get_or_fallback("error");
+    let on_surface_variant = scheme.get_or_fallback("on_surface_variant");
+    let secondary = scheme.get_or_fallback("secondary");
+
+    // ansi0 "black": a dark tone on dark bg, near-black fg tone on light
+    let black = if is_light {
+        foreground.clone()
+    } else {
+        scheme.get_or_fallback("surface_container_high")
+    };
+
+    // Slot order: black, red, green, yellow, blue, magenta, cyan, white.
+    // blue/magenta carry the scheme accents (primary/secondary); the
+    // hue-critical slots use the fixed per-mode values above.
+    let ansi = [
+        black,
+        error.clone(),
+        hues.green.to_string(),
+        hues.yellow.to_string(),
+        cursor_bg.clone(),
+        secondary.clone(),
+        hues.cyan.to_string(),
+        on_surface_variant,
+    ];
+
+    let brights = [
+        outline,
+        error,
+        hues.bright_green.to_string(),
+        hues.bright_yellow.to_string(),
+        cursor_bg.clone(),
+        secondary,
+        hues.bright_cyan.to_string(),
+        foreground.clone(),
+    ];
+
+    TermColors {
+        foreground,
+        background: scheme.get_or_fallback("surface"),
+        cursor_bg,
+        cursor_fg: scheme.get_or_fallback("on_primary"),
+        selection_bg: scheme.get_or_fallback("primary_container"),
+        selection_fg: scheme.get_or_fallback("on_primary_container"),
+        ansi,
+        brights,
+    }
+}
+
+/// Diagnostic colors for editor themes: error from the scheme; warning,
+/// info and hint from the optional custom tokens of the same name (colors
+/// JSON or `[colors]` overrides), else the conventional hue or an accent.
+pub(crate) struct Diagnostics {
+    pub(crate) error: String,
+    pub(crate) warning: String,
+    pub(crate) info: String,
+    pub(crate) hint: String,
+}
+
+pub(crate) fn diagnostics(scheme: &ColorScheme) -> Diagnostics {
+    let hues = if scheme.mode == ThemeMode::Light {
+        &LIGHT_HUES
+    } else {
+        &DARK_HUES
+    };
+    let token = |key: &str, default: String| scheme.get(key).cloned().unwrap_or(default);
+    Diagnostics {
+        error: scheme.get_or_fallback("error"),
+        warning: token("warning", hues.yellow.to_string()),
+        info: token("info", scheme.get_or_fallback("primary")),
+        hint: token("hint", scheme.get_or_fallback("tertiary")),
+    }
+}
+
+impl TermColors {
+    /// OSC color sequence: 10 = foreground, 11 = background, 12 = cursor,
+    /// 17/19 = selection bg/fg, 4;n = palette slot n (0-7 ansi, 8-15 brights).
+    pub(crate) fn osc_payload(&self) -> String {
+        let mut osc = String::new();
+        osc.push_str(&format!("\x1b]10;{}\x07", self.foreground));
+        osc.push_str(&format!("\x1b]11;{}\x07", self.background));
+        osc.push_str(&format!("\x1b]12;{}\x07", self.cursor_bg));
+        osc.push_str(&format!("\x1b]17;{}\x07", self.selection_bg));
+        osc.push_str(&format!("\x1b]19;{}\x07", self.selection_fg));
+        osc.push_str("\x1b]4");
+        for (i, color) in self.ansi.iter().chain(self.brights.iter()).enumerate() {
+            osc.push_str(&format!(";{};{}", i, color));
+        }
+        osc.push('\x07');
+        osc
+    }
+}
+
+/// Write an OSC payload to one terminal tty. Returns whether it was written.
+/// Bounded: a tty whose reader is stopped (Ctrl-S / a full pty buffer) would
+/// otherwise block write_all forever and hang the module until the registry
+/// timeout fails the switch.
+pub(crate) async fn write_osc(tty: &str, osc: &str) -> bool {
+    let one = async {
+        use tokio::io::AsyncWriteExt;
+        let mut f = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(tty)
+            .await
+            .ok()?;
+        f.write_all(osc.as_bytes()).await.ok()?;
+        Some(())
+    };
+    match tokio::time::timeout(std::time::Duration::from_millis(200), one).await {
+        Ok(Some(())) => true,
+        Ok(None) => false,
+        Err(_) => {
+            tracing::debug!("Timed out writing OSC to {}, skipping", tty);
+            false
+        }
+    }
+}
@@ -18,6 +18,24 @@ impl TmuxModule {
     }
 }
 
+/// Global options lmtt sets, shared by the colors file and live frames.
+fn tmux_options(scheme: &ColorScheme) -> [(&'static str, String); 4] {
+    let primary = scheme.get_or_fallback("primary");
+    let on_primary = scheme.get_or_fallback("on_primary");
+    let surface = scheme.get_or_fallback("surface");
+    let on_surface = scheme.get_or_fallback("on_surface");
+    let outline_variant = scheme.get_or_fallback("outline_variant");
+
+    [
+        ("status-style", format!("bg={} fg={}", surface, on_surface)),
+        ("pane-active-border-style", format!("fg={}", primary)),
+        // outline_variant, not surface: a border the color of the background
+        // is invisible
+        ("pane-border-style", format!("fg={}", outline_variant)),
+        ("message-style", format!("bg={} fg={}", primary, on_primary)),
+    ]
+}
+
 #[async_trait]
 impl ThemeModule for TmuxModule {
     fn name(&self) -> &'static str {
@@ -28,6 +46,10 @@ impl ThemeModule for TmuxModule {
         "tmux"
     }
 
+    fn supports_live_recolor(&self) -> bool {
+        true
+    }
+
     async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
         let colors_conf = dirs::config_dir()
             .ok_or(lmtt_core::Error::Config("No config dir".into()))?
@@ -38,37 +60,22 @@ impl ThemeModule for TmuxModule {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let primary = scheme.get_or_fallback("primary");
-        let on_primary = scheme.get_or_fallback("on_primary");
-        let surface = scheme.get_or_fallback("surface");
-        let on_surface = scheme.get_or_fallback("on_surface");
-        let outline_variant = scheme.get_or_fallback("outline_variant");
-
         let mut content = String::new();
         content.push_str("# Tmux colors generated by lmtt\n\n");
-        content.push_str(&format!(
-            "set -g status-style 'bg={} fg={}'\n",
-            surface, on_surface
-        ));
-        content.push_str(&format!(
-            "set -g pane-active-border-style 'fg={}'\n",
-            primary
-        ));
-        // outline_variant, not surface: a border the color of the background
-        // is invisible
-        content.push_str(&format!(
-            "set -g pane-border-style 'fg={}'\n",
-            outline_variant
-        ));
-        content.push_str(&format!(
-            "set -g message-style 'bg={} fg={}'\n",
-            primary, on_primary
-        ));
+        for (option, value) in tmux_options(scheme) {
+            content.push_str(&format!("set -g {} '{}'\n", option, value));
+        }
 
         lmtt_core::fsutil::write_atomic(&colors_conf, content).await?;
 
         tracing::info!("[Tmux] Updated colors at {}", colors_conf.display());
 
+        // The server renames itself "tmux: server" on Linux.
+        if !crate::process_running("tmux|tmux: server").await {
+            tracing::debug!("[Tmux] No running server to reload");
+            return Ok(());
+        }
+
         // Re-source into a running server so attached sessions update live.
         // "no server running" is normal — only log real failures.
         let reload = tokio::process::Command::new("tmux")
@@ -95,6 +102,33 @@ impl ThemeModule for TmuxModule {
         Ok(())
     }
 
+    async fn apply_frame(&self, scheme: &ColorScheme) -> Result<()> {
+        // One tmux invocation: commands separated by a bare ";" argument
+        let mut args: Vec<String> = Vec::new();
+        for (option, value) in tmux_options(scheme) {
+            if !args.is_empty() {
+                args.push(";".to_string());
+            }
+            args.extend([
+                "set".to_string(),
+                "-g".to_string(),
+                option.to_string(),
+                value,
+            ]);
+        }
+        let output = tokio::process::Command::new("tmux")
+            .args(&args)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(lmtt_core::Error::Module(format!(
+                "tmux set failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
     async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
         let home = dirs::home_dir().ok_or(lmtt_core::Error::Config("No home dir".into()))?;
         let config_dir =
@@ -1,6 +1,8 @@
+use crate::terminal;
 use crate::{ConfigFileInfo, ThemeModule};
 use async_trait::async_trait;
 use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
+use std::path::{Path, PathBuf};
 
 crate::register_module!(VSCodeModule);
 
@@ -20,6 +22,415 @@ impl VSCodeModule {
 
 const THEME_KEY: &str = "workbench.colorTheme";
 
+/// Profile `vscode_theme` that opts into the generated theme extension.
+const GENERATED_THEME: &str = "lmtt";
+
+/// Directory name of the generated extension inside each extensions dir.
+const EXTENSION_DIR: &str = "lmtt-theme";
+const EXTENSION_ID: &str = "lmtt.lmtt-theme";
+const EXTENSION_VERSION: &str = "1.0.0";
+
+fn theme_label(mode: ThemeMode) -> &'static str {
+    match mode {
+        ThemeMode::Light => "LMTT Light",
+        ThemeMode::Dark => "LMTT Dark",
+    }
+}
+
+/// User extension dirs per editor family: VS Code, VSCodium and Code - OSS
+/// (which share ~/.vscode-oss), and Cursor. Only editors that have run at
+/// least once (the parent dir exists) get the extension.
+fn extension_roots(home: &Path) -> Vec<PathBuf> {
+    [".vscode", ".vscode-oss", ".cursor"]
+        .iter()
+        .map(|dir| home.join(dir))
+        .filter(|dir| dir.is_dir())
+        .map(|dir| dir.join("extensions"))
+        .collect()
+}
+
+fn package_json() -> String {
+    let themes: Vec<serde_json::Value> = [ThemeMode::Light, ThemeMode::Dark]
+        .into_iter()
+        .map(|mode| {
+            serde_json::json!({
+                "label": theme_label(mode),
+                "uiTheme": if mode == ThemeMode::Light { "vs" } else { "vs-dark" },
+                "path": format!("./themes/lmtt-{}.json", mode),
+            })
+        })
+        .collect();
+    let package = serde_json::json!({
+        "name": EXTENSION_DIR,
+        "displayName": "LMTT",
+        "description": "Color theme generated by lmtt from the current palette",
+        "publisher": "lmtt",
+        "version": EXTENSION_VERSION,
+        "engines": { "vscode": "^1.60.0" },
+        "categories": ["Themes"],
+        "contributes": { "themes": themes },
+    });
+    serde_json::to_string_pretty(&package).unwrap_or_default()
+}
+
+/// A complete color theme: workbench colors, the integrated terminal, and
+/// TextMate tokenColors plus semantic token colors for the editor.
+fn theme_json(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let diag = terminal::diagnostics(scheme);
+
+    let surface = c("surface");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let container_low = c("surface_container_low");
+    let container = c("surface_container");
+    let container_high = c("surface_container_high");
+    let container_highest = c("surface_container_highest");
+    let primary = c("primary");
+    let on_primary = c("on_primary");
+    let primary_container = c("primary_container");
+    let on_primary_container = c("on_primary_container");
+    let secondary = c("secondary");
+    let secondary_container = c("secondary_container");
+    let on_secondary_container = c("on_secondary_container");
+    let tertiary = c("tertiary");
+    let outline = c("outline");
+    let outline_variant = c("outline_variant");
+    let green = term.ansi[2].clone();
+    let yellow = term.ansi[3].clone();
+    let cyan = term.ansi[6].clone();
+    // Highlight backgrounds: the hue washed most of the way into the
+    // background (opaque, so #rgb and #rrggbbaa tokens work too)
+    let wash = |color: &str| {
+        lmtt_core::colors::mix_oklab(color, &surface, 0.8).unwrap_or_else(|_| surface.clone())
+    };
+
+    let mut colors = serde_json::Map::new();
+    let mut set = |key: &str, value: &str| {
+        colors.insert(key.to_string(), serde_json::Value::from(value));
+    };
+    // Base
+    set("foreground", &on_surface);
+    set("descriptionForeground", &on_surface_variant);
+    set("errorForeground", &diag.error);
+    set("focusBorder", &primary);
+    set("selection.background", &primary_container);
+    set("widget.border", &outline_variant);
+    set("textLink.foreground", &primary);
+    set("textLink.activeForeground", &tertiary);
+    // Editor
+    set("editor.background", &surface);
+    set("editor.foreground", &on_surface);
+    set("editorCursor.foreground", &primary);
+    set("editor.selectionBackground", &container_highest);
+    set("editor.inactiveSelectionBackground", &container_high);
+    set("editor.selectionHighlightBackground", &container_high);
+    set("editor.wordHighlightBackground", &container_high);
+    set("editor.findMatchBackground", &wash(&tertiary));
+    set("editor.findMatchBorder", &tertiary);
+    set("editor.findMatchHighlightBackground", &container_highest);
+    set("editor.lineHighlightBackground", &container_low);
+    set("editorLineNumber.foreground", &outline);
+    set("editorLineNumber.activeForeground", &primary);
+    set("editorIndentGuide.background1", &outline_variant);
+    set("editorIndentGuide.activeBackground1", &outline);
+    set("editorWhitespace.foreground", &outline_variant);
+    set("editorRuler.foreground", &outline_variant);
+    set("editorBracketMatch.background", &container_highest);
+    set("editorBracketMatch.border", &primary);
+    set("editorError.foreground", &diag.error);
+    set("editorWarning.foreground", &diag.warning);
+    set("editorInfo.foreground", &diag.info);
+    set("editorHint.foreground", &diag.hint);
+    set("editorInlayHint.foreground", &outline);
+    set("editorInlayHint.background", &container_low);
+    set("editorGutter.addedBackground", &green);
+    set("editorGutter.modifiedBackground", &yellow);
+    set("editorGutter.deletedBackground", &diag.error);
+    set("diffEditor.insertedTextBackground", &wash(&green));
+    set("diffEditor.removedTextBackground", &wash(&diag.error));
+    set("editorWidget.background", &container);
+    set("editorWidget.border", &outline_variant);
+    set("editorSuggestWidget.background", &container);
+    set("editorSuggestWidget.selectedBackground", &primary_container);
+    set(
+        "editorSuggestWidget.selectedForeground",
+        &on_primary_container,
+    );
+    set("editorSuggestWidget.highlightForeground", &primary);
+    set("editorHoverWidget.background", &container);
+    set("editorHoverWidget.border", &outline_variant);
+    set("peekView.border", &primary);
+    set("peekViewEditor.background", &container_low);
+    set("peekViewResult.background", &container);
+    set("peekViewTitle.background", &container_high);
+    set("editorGroupHeader.tabsBackground", &container_low);
+    set("editorGroup.border", &outline_variant);
+    // Tabs
+    set("tab.activeBackground", &surface);
+    set("tab.activeForeground", &on_surface);
+    set("tab.activeBorderTop", &primary);
+    set("tab.inactiveBackground", &container_low);
+    set("tab.inactiveForeground", &on_surface_variant);
+    set("tab.border", &container_low);
+    // Chrome
+    set("titleBar.activeBackground", &container_low);
+    set("titleBar.activeForeground", &on_surface);
+    set("titleBar.inactiveBackground", &container_low);
+    set("titleBar.inactiveForeground", &on_surface_variant);
+    set("activityBar.background", &container_low);
+    set("activityBar.foreground", &primary);
+    set("activityBar.inactiveForeground", &on_surface_variant);
+    set("activityBar.activeBorder", &primary);
+    set("activityBarBadge.background", &primary);
+    set("activityBarBadge.foreground", &on_primary);
+    set("sideBar.background", &container_low);
+    set("sideBar.foreground", &on_surface);
+    set("sideBar.border", &outline_variant);
+    set("sideBarTitle.foreground", &on_surface);
+    set("sideBarSectionHeader.background", &container);
+    set("sideBarSectionHeader.foreground", &on_surface);
+    set("statusBar.background", &container);
+    set("statusBar.foreground", &on_surface);
+    set("statusBar.debuggingBackground", &tertiary);
+    set("statusBar.noFolderBackground", &container);
+    set("statusBarItem.remoteBackground", &primary);
+    set("statusBarItem.remoteForeground", &on_primary);
+    set("panel.background", &container_low);
+    set("panel.border", &outline_variant);
+    set("panelTitle.activeForeground", &on_surface);
+    set("panelTitle.activeBorder", &primary);
+    set("panelTitle.inactiveForeground", &on_surface_variant);
+    set("badge.background", &secondary_container);
+    set("badge.foreground", &on_secondary_container);
+    set("button.background", &primary);
+    set("button.foreground", &on_primary);
+    set("button.hoverBackground", &tertiary);
+    set("button.secondaryBackground", &secondary_container);
+    set("button.secondaryForeground", &on_secondary_container);
+    set("input.background", &container);
+    set("input.foreground", &on_surface);
+    set("input.border", &outline_variant);
+    set("input.placeholderForeground", &outline);
+    set("inputOption.activeBorder", &primary);
+    set("dropdown.background", &container);
+    set("dropdown.foreground", &on_surface);
+    set("dropdown.border", &outline_variant);
+    set("list.activeSelectionBackground", &primary_container);
+    set("list.activeSelectionForeground", &on_primary_container);
+    set("list.inactiveSelectionBackground", &container_high);
+    set("list.hoverBackground", &container);
+    set("list.focusOutline", &primary);
+    set("list.highlightForeground", &primary);
+    set("menu.background", &container);
+    set("menu.foreground", &on_surface);
+    set("menu.selectionBackground", &primary_container);
+    set("menu.selectionForeground", &on_primary_container);
+    set("quickInput.background", &container);
+    set("scrollbarSlider.background", &outline_variant);
+    set("scrollbarSlider.hoverBackground", &outline);
+    set("scrollbarSlider.activeBackground", &primary);
+    set("progressBar.background", &primary);
+    set("notifications.background", &container);
+    set("notifications.foreground", &on_surface);
+    set("gitDecoration.addedResourceForeground", &green);
+    set("gitDecoration.modifiedResourceForeground", &yellow);
+    set("gitDecoration.deletedResourceForeground", &diag.error);
+    set("gitDecoration.untrackedResourceForeground", &cyan);
+    set("gitDecoration.ignoredResourceForeground", &outline);
+    // Integrated terminal
+    set("terminal.background", &term.background);
+    set("terminal.foreground", &term.foreground);
+    set("terminalCursor.foreground", &term.cursor_bg);
+    set("terminal.selectionBackground", &term.selection_bg);
+    const SLOTS: [&str; 8] = [
+        "Black", "Red", "Green", "Yellow", "Blue", "Magenta", "Cyan", "White",
+    ];
+    for (slot, color) in SLOTS.iter().zip(&term.ansi) {
+        set(&format!("terminal.ansi{}", slot), color);
+    }
+    for (slot, color) in SLOTS.iter().zip(&term.brights) {
+        set(&format!("terminal.ansiBright{}", slot), color);
+    }
+
+    let token = |scope: &[&str], foreground: &str, font_style: Option<&str>| {
+        let mut settings = serde_json::json!({ "foreground": foreground });
+        if let Some(style) = font_style {
+            settings["fontStyle"] = serde_json::Value::from(style);
+        }
+        serde_json::json!({ "scope": scope, "settings": settings })
+    };
+    let token_colors = vec![
+        token(
+            &["comment", "punctuation.definition.comment"],
+            &outline,
+            Some("italic"),
+        ),
+        token(
+            &["string", "string.quoted", "markup.inline.raw"],
+            &green,
+            None,
+        ),
+        token(
+            &[
+                "constant.character.escape",
+                "string.regexp",
+                "constant.other.placeholder",
+            ],
+            &cyan,
+            None,
+        ),
+        token(
+            &["constant.numeric", "constant.language", "constant"],
+            &cyan,
+            None,
+        ),
+        token(
+            &[
+                "keyword",
+                "storage",
+                "storage.type",
+                "storage.modifier",
+                "keyword.control",
+            ],
+            &primary,
+            None,
+        ),
+        token(
+            &["keyword.operator", "punctuation"],
+            &on_surface_variant,
+            None,
+        ),
+        token(
+            &[
+                "entity.name.function",
+                "support.function",
+                "meta.function-call",
+            ],
+            &tertiary,
+            None,
+        ),
+        token(
+            &[
+                "entity.name.type",
+                "entity.name.class",
+                "support.type",
+                "support.class",
+                "entity.other.inherited-class",
+                "entity.name.namespace",
+            ],
+            &secondary,
+            None,
+        ),
+        token(&["variable", "meta.definition.variable"], &on_surface, None),
+        token(&["variable.parameter"], &on_surface_variant, None),
+        token(
+            &["variable.language", "support.variable"],
+            &secondary,
+            Some("italic"),
+        ),
+        token(&["entity.name.tag"], &primary, None),
+        token(&["entity.other.attribute-name"], &tertiary, None),
+        token(
+            &["markup.heading", "entity.name.section"],
+            &primary,
+            Some("bold"),
+        ),
+        token(&["markup.bold"], &on_surface, Some("bold")),
+        token(&["markup.italic"], &on_surface, Some("italic")),
+        token(
+            &["markup.underline.link", "string.other.link"],
+            &tertiary,
+            Some("underline"),
+        ),
+        token(&["markup.inserted"], &green, None),
+        token(&["markup.deleted"], &diag.error, None),
+        token(&["markup.changed"], &yellow, None),
+        token(&["invalid", "invalid.illegal"], &diag.error, None),
+    ];
+
+    let theme = serde_json::json!({
+        "name": theme_label(scheme.mode),
+        "type": scheme.mode.to_string(),
+        "semanticHighlighting": true,
+        "colors": colors,
+        "tokenColors": token_colors,
+        "semanticTokenColors": {
+            "parameter": on_surface_variant,
+            "variable.defaultLibrary": secondary,
+            "function.defaultLibrary": { "foreground": tertiary, "italic": true },
+            "type.defaultLibrary": { "foreground": secondary, "italic": true },
+            "namespace": secondary,
+            "enumMember": cyan,
+            "property": on_surface,
+        },
+    });
+    serde_json::to_string_pretty(&theme).unwrap_or_default()
+}
+
+/// Write (or refresh) the generated extension under one extensions dir. The
+/// current mode's theme is always rewritten; the other mode's only when
+/// there are cached colors for it or it doesn't exist yet.
+async fn write_extension(
+    extensions_dir: &Path,
+    scheme: &ColorScheme,
+    config: &Config,
+) -> Result<()> {
+    let root = extensions_dir.join(EXTENSION_DIR);
+    let themes_dir = root.join("themes");
+    tokio::fs::create_dir_all(&themes_dir).await?;
+
+    lmtt_core::fsutil::write_atomic(&root.join("package.json"), package_json()).await?;
+    let current = themes_dir.join(format!("lmtt-{}.json", scheme.mode));
+    lmtt_core::fsutil::write_atomic(&current, theme_json(scheme)).await?;
+
+    let other_mode = crate::opposite_mode(scheme.mode);
+    let other = themes_dir.join(format!("lmtt-{}.json", other_mode));
+    if let Some(other_scheme) = crate::companion_scheme(other_mode, config, &other).await {
+        lmtt_core::fsutil::write_atomic(&other, theme_json(&other_scheme)).await?;
+    }
+
+    register_extension(extensions_dir, &root).await;
+    Ok(())
+}
+
+/// Newer VS Code builds only load user extensions listed in
+/// `extensions.json`; add ours once. The file is strict JSON written by the
+/// editor itself, so a plain parse/serialize round trip is safe — and any
+/// surprise in it is left alone rather than rewritten.
+async fn register_extension(extensions_dir: &Path, root: &Path) {
+    let manifest = extensions_dir.join("extensions.json");
+    let Ok(content) = tokio::fs::read_to_string(&manifest).await else {
+        return;
+    };
+    let Ok(serde_json::Value::Array(mut entries)) = serde_json::from_str(&content) else {
+        tracing::debug!("[VSCode] Leaving unrecognized {} alone", manifest.display());
+        return;
+    };
+    let registered = entries.iter().any(|entry| {
+        entry
+            .pointer("/identifier/id")
+            .and_then(|id| id.as_str())
+            .is_some_and(|id| id.eq_ignore_ascii_case(EXTENSION_ID))
+    });
+    if registered {
+        return;
+    }
+    let path = root.to_string_lossy();
+    entries.push(serde_json::json!({
+        "identifier": { "id": EXTENSION_ID },
+        "version": EXTENSION_VERSION,
+        "location": { "$mid": 1, "path": path, "scheme": "file" },
+        "relativeLocation": EXTENSION_DIR,
+    }));
+    if let Ok(json) = serde_json::to_string(&entries) {
+        if let Err(e) = lmtt_core::fsutil::write_atomic(&manifest, json).await {
+            tracing::warn!("[VSCode] Could not register the theme extension: {}", e);
+        }
+    }
+}
+
 /// Set a top-level string key in a settings.json WITHOUT parsing it as JSON.
 /// VSCode settings are JSONC (comments, trailing commas) — round-tripping
 /// through serde_json rejects valid files, and any "fall back to empty map"
@@ -31,17 +442,38 @@ const THEME_KEY: &str = "workbench.colorTheme";
 /// object — never a match inside a comment, string value, or nested object —
 /// and replaces its entire value (even a non-string one) with the quoted new
 /// value. Returns None when no root object is found.
-fn set_string_key(content: &str, key: &str, value: &str) -> Option<String> {
+pub(crate) fn set_string_key(content: &str, key: &str, value: &str) -> Option<String> {
+    set_nested_string_key(content, &[key], value)
+}
+
+/// [`set_string_key`] for a key path into nested objects, e.g.
+/// `["theme", "dark"]`. Each level is matched with the same scan, scoped to
+/// the enclosing object. A missing object along the path is created, and a
+/// non-object value in the way (`"theme": "One Dark"`) is replaced by one.
+pub(crate) fn set_nested_string_key(content: &str, path: &[&str], value: &str) -> Option<String> {
     let escaped_value = format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
     let b = content.as_bytes();
 
     // Locate the root object's opening brace (skipping leading comments/ws).
-    let mut i = skip_trivia(b, 0);
+    let i = skip_trivia(b, 0);
     if i >= b.len() || b[i] != b'{' {
         return None;
     }
-    let root_brace = i;
-    i += 1;
+    set_in_object(content, i, path, &escaped_value, 1)
+}
+
+/// Set `path` inside the object opening at `brace`, whose keys sit at
+/// indent `level` (two spaces per level) when a new one must be inserted.
+fn set_in_object(
+    content: &str,
+    brace: usize,
+    path: &[&str],
+    escaped_value: &str,
+    level: usize,
+) -> Option<String> {
+    let (key, rest) = path.split_first()?;
+    let b = content.as_bytes();
+    let mut i = brace + 1;
     let mut depth = 1;
 
     while i < b.len() {
@@ -53,6 +485,10 @@ fn set_string_key(content: &str, key: &str, value: &str) -> Option<String> {
             b'}' | b']' => {
                 depth -= 1;
                 i += 1;
+                // End of this object: don't wander into its siblings.
+                if depth == 0 {
+                    break;
+                }
             }
             b'{' | b'[' => {
                 depth += 1;
@@ -61,13 +497,17 @@ fn set_string_key(content: &str, key: &str, value: &str) -> Option<String> {
             b'"' => {
                 let (s, after) = read_json_string(b, i)?;
                 let mut j = skip_trivia(b, after);
-                // A string directly inside the root object followed by ':' is a key.
-                if depth == 1 && j < b.len() && b[j] == b':' && s == key {
+                // A string directly inside this object followed by ':' is a key.
+                if depth == 1 && j < b.len() && b[j] == b':' && s == *key {
                     j = skip_trivia(b, j + 1);
+                    if !rest.is_empty() && b.get(j) == Some(&b'{') {
+                        return set_in_object(content, j, rest, escaped_value, level + 1);
+                    }
                     let value_end = scan_value_end(b, j)?;
-                    let mut out = String::with_capacity(content.len() + escaped_value.len());
+                    let new_value = nested_value(rest, escaped_value, level);
+                    let mut out = String::with_capacity(content.len() + new_value.len());
                     out.push_str(&content[..j]);
-                    out.push_str(&escaped_value);
+                    out.push_str(&new_value);
                     out.push_str(&content[value_end..]);
                     return Some(out);
                 }
@@ -77,14 +517,31 @@ fn set_string_key(content: &str, key: &str, value: &str) -> Option<String> {
         }
     }
 
-    // Key absent: insert right after the root object's opening brace.
-    let mut out = String::with_capacity(content.len() + escaped_value.len() + key.len() + 16);
-    out.push_str(&content[..=root_brace]);
-    out.push_str(&format!("\n  \"{}\": {},", key, escaped_value));
-    out.push_str(&content[root_brace + 1..]);
+    // Key absent: insert right after the object's opening brace.
+    let new_value = nested_value(rest, escaped_value, level);
+    let indent = "  ".repeat(level);
+    let mut out = String::with_capacity(content.len() + new_value.len() + key.len() + 16);
+    out.push_str(&content[..=brace]);
+    out.push_str(&format!("\n{}\"{}\": {},", indent, key, new_value));
+    out.push_str(&content[brace + 1..]);
     Some(out)
 }
 
+/// The value for a key at `level`: the quoted string itself, or the objects
+/// still missing along the rest of the path.
+fn nested_value(rest: &[&str], escaped_value: &str, level: usize) -> String {
+    match rest.split_first() {
+        None => escaped_value.to_string(),
+        Some((key, rest)) => format!(
+            "{{\n{}\"{}\": {}\n{}}}",
+            "  ".repeat(level + 1),
+            key,
+            nested_value(rest, escaped_value, level + 1),
+            "  ".repeat(level)
+        ),
+    }
+}
+
 /// Advance past JSON whitespace and // line / /* block */ comments.
 fn skip_trivia(b: &[u8], mut i: usize) -> usize {
     loop {
@@ -219,13 +676,31 @@ impl ThemeModule for VSCodeModule {
             &config.theme_profiles.dark
         };
 
-        let theme = profile.vscode_theme.as_deref().unwrap_or({
-            if is_light {
-                "Default Light+"
-            } else {
-                "Default Dark+"
+        let generated = profile
+            .vscode_theme
+            .as_deref()
+            .is_some_and(|theme| theme.eq_ignore_ascii_case(GENERATED_THEME));
+
+        // The generated theme alternates between two labels, so every
+        // toggle changes the setting and the editor re-reads the theme file.
+        let theme = if generated {
+            for extensions_dir in extension_roots(&home) {
+                write_extension(&extensions_dir, scheme, config).await?;
+                tracing::info!(
+                    "[VSCode] Updated theme extension in {}",
+                    extensions_dir.display()
+                );
             }
-        });
+            theme_label(scheme.mode)
+        } else {
+            profile.vscode_theme.as_deref().unwrap_or({
+                if is_light {
+                    "Default Light+"
+                } else {
+                    "Default Dark+"
+                }
+            })
+        };
 
         let mut updated_count = 0;
         let mut errors: Vec<String> = Vec::new();
@@ -307,6 +782,65 @@ mod tests {
         assert!(out.contains("\\\"B\\\""));
     }
 
+    #[test]
+    fn generated_theme_matches_its_manifest_entry() {
+        let mut scheme = ColorScheme::new(ThemeMode::Dark);
+        scheme.set("surface".into(), "#101418".into());
+        let theme: serde_json::Value = serde_json::from_str(&theme_json(&scheme)).unwrap();
+        assert_eq!(theme["name"], "LMTT Dark");
+        assert_eq!(theme["type"], "dark");
+        assert_eq!(theme["colors"]["editor.background"], "#101418");
+        assert_eq!(theme["colors"]["terminal.background"], "#101418");
+        assert!(theme["tokenColors"].as_array().unwrap().len() > 10);
+
+        let package: serde_json::Value = serde_json::from_str(&package_json()).unwrap();
+        let themes = package["contributes"]["themes"].as_array().unwrap();
+        assert!(themes
+            .iter()
+            .any(|t| t["label"] == "LMTT Dark" && t["path"] == "./themes/lmtt-dark.json"));
+    }
+
+    #[test]
+    fn nested_key_creates_and_reuses_objects() {
+        let input = "{\n  // editor\n  \"theme\": \"One Dark\",\n  \"ui_font_size\": 16\n}";
+        let mut out = input.to_string();
+        for (key, value) in [
+            ("dark", "LMTT Dark"),
+            ("light", "LMTT Light"),
+            ("mode", "system"),
+        ] {
+            out = set_nested_string_key(&out, &["theme", key], value).unwrap();
+        }
+        assert!(out.contains("// editor"));
+        assert!(out.contains("\"ui_font_size\": 16"));
+        assert!(!out.contains("One Dark"));
+        let json: String = out
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("//"))
+            .collect();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["theme"]["mode"], "system");
+        assert_eq!(parsed["theme"]["light"], "LMTT Light");
+        assert_eq!(parsed["theme"]["dark"], "LMTT Dark");
+
+        // Second pass edits in place
+        let again = set_nested_string_key(&out, &["theme", "mode"], "system").unwrap();
+        assert_eq!(again, out);
+    }
+
+    #[test]
+    fn nested_key_does_not_match_sibling_objects() {
+        let input = r#"{"theme": {"mode": "light"}, "other": {"mode": "keep"}}"#;
+        let out = set_nested_string_key(input, &["theme", "mode"], "system").unwrap();
+        assert_eq!(
+            out,
+            r#"{"theme": {"mode": "system"}, "other": {"mode": "keep"}}"#
+        );
+        let out = set_nested_string_key(input, &["theme", "dark"], "LMTT Dark").unwrap();
+        assert!(out.contains(r#""other": {"mode": "keep"}"#));
+        assert!(out.starts_with("{\"theme\": {\n    \"dark\": \"LMTT Dark\","));
+    }
+
     #[test]
     fn returns_none_on_no_object() {
         assert!(set_string_key("", THEME_KEY, "X").is_none());
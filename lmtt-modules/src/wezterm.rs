@@ -1,3 +1,4 @@
+use crate::terminal::{self, TermColors};
 use crate::{ConfigFileInfo, ThemeModule};
 use async_trait::async_trait;
 use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
@@ -18,61 +19,6 @@ impl WeztermModule {
     }
 }
 
-/// Fixed ANSI hues per mode for the slots Material You has no semantic token
-/// for. Green must be green and cyan must be cyan — `ls`, diffs, and TUIs
-/// depend on the conventional hue of each slot.
-struct AnsiHues {
-    green: &'static str,
-    bright_green: &'static str,
-    yellow: &'static str,
-    bright_yellow: &'static str,
-    cyan: &'static str,
-    bright_cyan: &'static str,
-}
-
-const DARK_HUES: AnsiHues = AnsiHues {
-    green: "#98c379",
-    bright_green: "#b5e890",
-    yellow: "#e5c07b",
-    bright_yellow: "#ecd09b",
-    cyan: "#56b6c2",
-    bright_cyan: "#7bdfec",
-};
-
-const LIGHT_HUES: AnsiHues = AnsiHues {
-    green: "#50a14f",
-    bright_green: "#3f8e3e",
-    yellow: "#c18401",
-    bright_yellow: "#a06d00",
-    cyan: "#0184bc",
-    bright_cyan: "#016d9c",
-};
-
-/// OSC color sequence: 10 = foreground, 11 = background, 12 = cursor,
-/// 17/19 = selection bg/fg, 4;n = palette slot n (0-7 ansi, 8-15 brights).
-fn build_osc_payload(
-    foreground: &str,
-    background: &str,
-    cursor: &str,
-    selection_bg: &str,
-    selection_fg: &str,
-    ansi: &[String; 8],
-    brights: &[String; 8],
-) -> String {
-    let mut osc = String::new();
-    osc.push_str(&format!("\x1b]10;{}\x07", foreground));
-    osc.push_str(&format!("\x1b]11;{}\x07", background));
-    osc.push_str(&format!("\x1b]12;{}\x07", cursor));
-    osc.push_str(&format!("\x1b]17;{}\x07", selection_bg));
-    osc.push_str(&format!("\x1b]19;{}\x07", selection_fg));
-    osc.push_str("\x1b]4");
-    for (i, color) in ansi.iter().chain(brights.iter()).enumerate() {
-        osc.push_str(&format!(";{};{}", i, color));
-    }
-    osc.push('\x07');
-    osc
-}
-
 /// Write the OSC payload to every wezterm pane tty. Failures are per-pane
 /// and non-fatal (a pane may have vanished between list and write).
 async fn apply_osc_to_panes(osc: &str) {
@@ -104,23 +50,8 @@ async fn apply_osc_to_panes(osc: &str) {
 
     let mut updated = 0;
     for tty in ttys {
-        // Bound each open+write: a pane whose reader is stopped (Ctrl-S / a
-        // full pty buffer) would otherwise block write_all forever and hang
-        // the whole module until the registry timeout fails the switch.
-        let one = async {
-            use tokio::io::AsyncWriteExt;
-            let mut f = tokio::fs::OpenOptions::new()
-                .write(true)
-                .open(tty)
-                .await
-                .ok()?;
-            f.write_all(osc.as_bytes()).await.ok()?;
-            Some(())
-        };
-        match tokio::time::timeout(std::time::Duration::from_millis(200), one).await {
-            Ok(Some(())) => updated += 1,
-            Ok(None) => {}
-            Err(_) => tracing::debug!("[WezTerm] Timed out writing OSC to {}, skipping", tty),
+        if terminal::write_osc(tty, osc).await {
+            updated += 1;
         }
     }
     if updated > 0 {
@@ -138,6 +69,10 @@ impl ThemeModule for WeztermModule {
         "wezterm"
     }
 
+    fn supports_live_recolor(&self) -> bool {
+        true
+    }
+
     async fn apply(&self, scheme: &ColorScheme, config: &Config) -> Result<()> {
         let config_dir = dirs::config_dir()
             .ok_or(lmtt_core::Error::Config("No config dir".into()))?
@@ -149,56 +84,24 @@ impl ThemeModule for WeztermModule {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let is_light = scheme.mode == ThemeMode::Light;
-        let profile = if is_light {
+        let profile = if scheme.mode == ThemeMode::Light {
             &config.theme_profiles.light
         } else {
             &config.theme_profiles.dark
         };
-        let hues = if is_light { &LIGHT_HUES } else { &DARK_HUES };
 
-        let foreground = scheme.get_or_fallback("on_surface");
-        let background = scheme.get_or_fallback("surface");
-        let cursor_bg = scheme.get_or_fallback("primary");
-        let cursor_fg = scheme.get_or_fallback("on_primary");
-        let selection_bg = scheme.get_or_fallback("primary_container");
-        let selection_fg = scheme.get_or_fallback("on_primary_container");
-        let outline = scheme.get_or_fallback("outline");
-        let error = scheme.get_or_fallback("error");
+        let colors = terminal::term_colors(scheme);
+        let TermColors {
+            foreground,
+            background,
+            cursor_bg,
+            cursor_fg,
+            selection_bg,
+            selection_fg,
+            ansi,
+            brights,
+        } = &colors;
         let on_surface_variant = scheme.get_or_fallback("on_surface_variant");
-        let secondary = scheme.get_or_fallback("secondary");
-
-        // ansi0 "black": a dark tone on dark bg, near-black fg tone on light
-        let black = if is_light {
-            foreground.clone()
-        } else {
-            scheme.get_or_fallback("surface_container_high")
-        };
-
-        // Slot order: black, red, green, yellow, blue, magenta, cyan, white.
-        // blue/magenta carry the scheme accents (primary/secondary); the
-        // hue-critical slots use the fixed per-mode values above.
-        let ansi = [
-            black,
-            error.clone(),
-            hues.green.to_string(),
-            hues.yellow.to_string(),
-            cursor_bg.clone(),
-            secondary.clone(),
-            hues.cyan.to_string(),
-            on_surface_variant.clone(),
-        ];
-
-        let brights = [
-            outline.clone(),
-            error.clone(),
-            hues.bright_green.to_string(),
-            hues.bright_yellow.to_string(),
-            cursor_bg.clone(),
-            secondary.clone(),
-            hues.bright_cyan.to_string(),
-            foreground.clone(),
-        ];
 
         let mut content = String::new();
         content.push_str("-- WezTerm colors generated by lmtt\n");
@@ -211,12 +114,12 @@ impl ThemeModule for WeztermModule {
         content.push_str(&format!("  selection_fg = '{}',\n", selection_fg));
         content.push_str(&format!("  selection_bg = '{}',\n", selection_bg));
         content.push_str("  ansi = {\n");
-        for color in &ansi {
+        for color in ansi {
             content.push_str(&format!("    '{}',\n", color));
         }
         content.push_str("  },\n");
         content.push_str("  brights = {\n");
-        for color in &brights {
+        for color in brights {
             content.push_str(&format!("    '{}',\n", color));
         }
         content.push_str("  },\n");
@@ -290,18 +193,17 @@ impl ThemeModule for WeztermModule {
         // trigger, so configs should run automatically_reload_config=false
         // and rely on this for live updates (new panes read the colors file
         // at spawn). NEVER signal wezterm-gui: it has no reload signal
-        // handler and SIGUSR1's default disposition terminates it.
-        let osc = build_osc_payload(
-            &foreground,
-            &background,
-            &cursor_bg,
-            &selection_bg,
-            &selection_fg,
-            &ansi,
-            &brights,
-        );
-        apply_osc_to_panes(&osc).await;
+        // handler and SIGUSR1's default disposition terminates it. The mux
+        // server's name is truncated to 15 characters, as pgrep sees it.
+        if crate::process_running("wezterm-gui|wezterm-mux-ser").await {
+            apply_osc_to_panes(&colors.osc_payload()).await;
+        }
+
+        Ok(())
+    }
 
+    async fn apply_frame(&self, scheme: &ColorScheme) -> Result<()> {
+        apply_osc_to_panes(&terminal::term_colors(scheme).osc_payload()).await;
         Ok(())
     }
 
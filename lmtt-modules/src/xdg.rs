@@ -134,7 +134,9 @@ impl ThemeModule for XdgModule {
     }
 }
 
-async fn portal_read_one(
+/// `org.freedesktop.portal.Settings.ReadOne` for an appearance key. The error
+/// is the D-Bus error text (e.g. "Requested setting not found").
+pub async fn portal_read_one(
     conn: &zbus::Connection,
     key: &str,
 ) -> std::result::Result<zbus::zvariant::OwnedValue, String> {
@@ -0,0 +1,367 @@
+use crate::terminal;
+use crate::vscode::set_nested_string_key;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result, ThemeMode};
+use std::path::PathBuf;
+
+crate::register_module!(ZedModule);
+
+pub struct ZedModule;
+
+impl Default for ZedModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZedModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const THEME_SCHEMA: &str = "https://zed.dev/schema/themes/v0.2.0.json";
+
+/// Binary names across distros: upstream installs `zed`, Arch and Fedora
+/// ship `zeditor` (`zed` clashes with an older package), others `zedit`.
+const BINARIES: [&str; 4] = ["zed", "zeditor", "zedit", "zed-editor"];
+
+fn zed_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("zed"))
+}
+
+fn theme_name(mode: ThemeMode) -> &'static str {
+    match mode {
+        ThemeMode::Light => "LMTT Light",
+        ThemeMode::Dark => "LMTT Dark",
+    }
+}
+
+/// One entry of the family's `themes` array: UI, status, terminal and
+/// syntax colors. Syntax roles follow the other generated editor themes.
+fn theme_entry(scheme: &ColorScheme) -> serde_json::Value {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let term = terminal::term_colors(scheme);
+    let diag = terminal::diagnostics(scheme);
+
+    let surface = c("surface");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let container_low = c("surface_container_low");
+    let container = c("surface_container");
+    let container_high = c("surface_container_high");
+    let container_highest = c("surface_container_highest");
+    let primary = c("primary");
+    let secondary = c("secondary");
+    let tertiary = c("tertiary");
+    let outline = c("outline");
+    let outline_variant = c("outline_variant");
+    let green = term.ansi[2].clone();
+    let yellow = term.ansi[3].clone();
+    let cyan = term.ansi[6].clone();
+    let wash = |color: &str| {
+        lmtt_core::colors::mix_oklab(color, &surface, 0.8).unwrap_or_else(|_| surface.clone())
+    };
+
+    let mut style = serde_json::Map::new();
+    let mut set = |key: &str, value: &str| {
+        style.insert(key.to_string(), serde_json::Value::from(value));
+    };
+    // Surfaces and borders
+    set("background", &container_low);
+    set("surface.background", &container_low);
+    set("elevated_surface.background", &container);
+    set("border", &outline_variant);
+    set("border.variant", &outline_variant);
+    set("border.focused", &primary);
+    set("border.selected", &primary);
+    set("border.transparent", &container_low);
+    set("border.disabled", &outline_variant);
+    set("element.background", &container);
+    set("element.hover", &container_high);
+    set("element.active", &container_highest);
+    set("element.selected", &container_highest);
+    set("element.disabled", &container_low);
+    set("ghost_element.background", &container_low);
+    set("ghost_element.hover", &container_high);
+    set("ghost_element.active", &container_highest);
+    set("ghost_element.selected", &container_highest);
+    set("drop_target.background", &wash(&primary));
+    // Text and icons
+    set("text", &on_surface);
+    set("text.muted", &on_surface_variant);
+    set("text.placeholder", &outline);
+    set("text.disabled", &outline);
+    set("text.accent", &primary);
+    set("icon", &on_surface);
+    set("icon.muted", &on_surface_variant);
+    set("icon.disabled", &outline);
+    set("icon.accent", &primary);
+    set("link_text.hover", &primary);
+    // Chrome
+    set("status_bar.background", &container_low);
+    set("title_bar.background", &container_low);
+    set("title_bar.inactive_background", &container_low);
+    set("toolbar.background", &surface);
+    set("tab_bar.background", &container_low);
+    set("tab.inactive_background", &container_low);
+    set("tab.active_background", &surface);
+    set("panel.background", &container_low);
+    set("panel.focused_border", &primary);
+    set("pane.focused_border", &primary);
+    set("search.match_background", &wash(&tertiary));
+    set("scrollbar.thumb.background", &outline_variant);
+    set("scrollbar.thumb.hover_background", &outline);
+    set("scrollbar.thumb.border", &outline_variant);
+    set("scrollbar.track.background", &surface);
+    set("scrollbar.track.border", &surface);
+    // Editor
+    set("editor.background", &surface);
+    set("editor.foreground", &on_surface);
+    set("editor.gutter.background", &surface);
+    set("editor.subheader.background", &container_low);
+    set("editor.active_line.background", &container_low);
+    set("editor.highlighted_line.background", &container);
+    set("editor.line_number", &outline);
+    set("editor.active_line_number", &primary);
+    set("editor.invisible", &outline_variant);
+    set("editor.wrap_guide", &outline_variant);
+    set("editor.active_wrap_guide", &outline);
+    set("editor.indent_guide", &outline_variant);
+    set("editor.indent_guide_active", &outline);
+    set("editor.document_highlight.read_background", &container_high);
+    set(
+        "editor.document_highlight.write_background",
+        &container_highest,
+    );
+    // Status colors, each with a washed background for inline hints
+    for (name, color) in [
+        ("error", &diag.error),
+        ("warning", &diag.warning),
+        ("info", &diag.info),
+        ("hint", &diag.hint),
+        ("success", &green),
+        ("created", &green),
+        ("modified", &yellow),
+        ("deleted", &diag.error),
+        ("conflict", &diag.warning),
+        ("renamed", &cyan),
+        ("predictive", &outline),
+        ("ignored", &outline),
+        ("hidden", &outline),
+        ("unreachable", &outline),
+    ] {
+        set(name, color);
+        set(&format!("{}.background", name), &wash(color));
+        set(&format!("{}.border", name), color);
+    }
+    // Integrated terminal
+    set("terminal.background", &term.background);
+    set("terminal.foreground", &term.foreground);
+    set("terminal.bright_foreground", &term.foreground);
+    set("terminal.dim_foreground", &on_surface_variant);
+    const SLOTS: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    for (slot, color) in SLOTS.iter().zip(&term.ansi) {
+        set(&format!("terminal.ansi.{}", slot), color);
+    }
+    for (slot, color) in SLOTS.iter().zip(&term.brights) {
+        set(&format!("terminal.ansi.bright_{}", slot), color);
+    }
+
+    let highlight = |color: &str, font_style: Option<&str>, bold: bool| {
+        serde_json::json!({
+            "color": color,
+            "font_style": font_style,
+            "font_weight": if bold { Some(700) } else { None },
+        })
+    };
+    let syntax = serde_json::json!({
+        "attribute": highlight(&tertiary, None, false),
+        "boolean": highlight(&cyan, None, false),
+        "comment": highlight(&outline, Some("italic"), false),
+        "comment.doc": highlight(&outline, Some("italic"), false),
+        "constant": highlight(&cyan, None, false),
+        "constructor": highlight(&secondary, None, false),
+        "embedded": highlight(&on_surface, None, false),
+        "emphasis": highlight(&on_surface, Some("italic"), false),
+        "emphasis.strong": highlight(&on_surface, None, true),
+        "enum": highlight(&secondary, None, false),
+        "function": highlight(&tertiary, None, false),
+        "hint": highlight(&outline, None, false),
+        "keyword": highlight(&primary, None, false),
+        "label": highlight(&primary, None, false),
+        "link_text": highlight(&tertiary, None, false),
+        "link_uri": highlight(&tertiary, Some("italic"), false),
+        "number": highlight(&cyan, None, false),
+        "operator": highlight(&on_surface_variant, None, false),
+        "predictive": highlight(&outline, Some("italic"), false),
+        "preproc": highlight(&primary, None, false),
+        "property": highlight(&on_surface, None, false),
+        "punctuation": highlight(&on_surface_variant, None, false),
+        "punctuation.bracket": highlight(&on_surface_variant, None, false),
+        "punctuation.delimiter": highlight(&on_surface_variant, None, false),
+        "punctuation.list_marker": highlight(&primary, None, false),
+        "punctuation.special": highlight(&primary, None, false),
+        "string": highlight(&green, None, false),
+        "string.escape": highlight(&cyan, None, false),
+        "string.regex": highlight(&cyan, None, false),
+        "string.special": highlight(&cyan, None, false),
+        "string.special.symbol": highlight(&cyan, None, false),
+        "tag": highlight(&primary, None, false),
+        "text.literal": highlight(&green, None, false),
+        "title": highlight(&primary, None, true),
+        "type": highlight(&secondary, None, false),
+        "variable": highlight(&on_surface, None, false),
+        "variable.special": highlight(&secondary, Some("italic"), false),
+        "variant": highlight(&secondary, None, false),
+    });
+
+    // The local cursor is player 0; collaborators cycle through the rest.
+    let players: Vec<serde_json::Value> = [&primary, &tertiary, &secondary, &green, &cyan]
+        .into_iter()
+        .map(|color| {
+            serde_json::json!({
+                "cursor": color,
+                "background": color,
+                "selection": wash(color),
+            })
+        })
+        .collect();
+    style.insert("players".into(), serde_json::Value::from(players));
+    style.insert("syntax".into(), syntax);
+
+    serde_json::json!({
+        "name": theme_name(scheme.mode),
+        "appearance": scheme.mode.to_string(),
+        "style": style,
+    })
+}
+
+/// The family's entry for `mode` from a previously written lmtt.json.
+fn existing_entry(content: &str, mode: ThemeMode) -> Option<serde_json::Value> {
+    let family: serde_json::Value = serde_json::from_str(content).ok()?;
+    family["themes"]
+        .as_array()?
+        .iter()
+        .find(|theme| theme["name"] == theme_name(mode))
+        .cloned()
+}
+
+fn family_json(themes: Vec<serde_json::Value>) -> String {
+    let family = serde_json::json!({
+        "$schema": THEME_SCHEMA,
+        "name": "LMTT",
+        "author": "lmtt",
+        "themes": themes,
+    });
+    serde_json::to_string_pretty(&family).unwrap_or_default()
+}
+
+#[async_trait]
+impl ThemeModule for ZedModule {
+    fn name(&self) -> &'static str {
+        "zed"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "zed"
+    }
+
+    fn is_installed(&self) -> bool {
+        BINARIES.iter().any(|bin| which::which(bin).is_ok())
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, config: &Config) -> Result<()> {
+        let dir = zed_dir()?;
+        let themes_dir = dir.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+        let theme_file = themes_dir.join("lmtt.json");
+
+        // Both appearances live in one family file. The other one comes from
+        // the cached colors for its mode, else is carried over from the
+        // previous file (fallback palette the first time).
+        let other_mode = crate::opposite_mode(scheme.mode);
+        let other = match crate::companion_scheme(other_mode, config, &theme_file).await {
+            Some(other_scheme) => theme_entry(&other_scheme),
+            None => {
+                let previous = tokio::fs::read_to_string(&theme_file)
+                    .await
+                    .unwrap_or_default();
+                existing_entry(&previous, other_mode).unwrap_or_else(|| {
+                    let mut fallback = ColorScheme::new(other_mode);
+                    fallback.colors = lmtt_core::fallback::fallback_colors(other_mode);
+                    theme_entry(&fallback)
+                })
+            }
+        };
+        let mut themes = vec![theme_entry(scheme), other];
+        // Light first, matching the order Zed's theme picker lists them
+        themes.sort_by_key(|theme| theme["appearance"] != "light");
+        lmtt_core::fsutil::write_atomic(&theme_file, family_json(themes)).await?;
+        tracing::info!("[Zed] Updated theme family at {}", theme_file.display());
+
+        // Zed watches both the themes dir and settings.json, so there is no
+        // reload step; "system" mode follows the portal color-scheme.
+        let settings = dir.join("settings.json");
+        if !settings.exists() {
+            tracing::debug!("[Zed] No settings.json; theme written only");
+            return Ok(());
+        }
+        let content = tokio::fs::read_to_string(&settings).await?;
+        let mut updated = content.clone();
+        for (key, value) in [
+            ("mode", "system"),
+            ("light", theme_name(ThemeMode::Light)),
+            ("dark", theme_name(ThemeMode::Dark)),
+        ] {
+            updated = set_nested_string_key(&updated, &["theme", key], value).ok_or_else(|| {
+                lmtt_core::Error::Module(format!(
+                    "could not safely edit {} (no object braces or malformed value)",
+                    settings.display()
+                ))
+            })?;
+        }
+        if updated != content {
+            lmtt_core::fsutil::write_atomic(&settings, updated).await?;
+            tracing::info!("[Zed] Updated {}", settings.display());
+        }
+
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_round_trips_the_other_appearance() {
+        let mut dark = ColorScheme::new(ThemeMode::Dark);
+        dark.set("surface".into(), "#101418".into());
+        let mut light = ColorScheme::new(ThemeMode::Light);
+        light.set("surface".into(), "#f8f9ff".into());
+
+        let content = family_json(vec![theme_entry(&light), theme_entry(&dark)]);
+        let family: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(family["themes"][0]["appearance"], "light");
+        assert_eq!(family["themes"][1]["name"], "LMTT Dark");
+        assert_eq!(family["themes"][1]["style"]["editor.background"], "#101418");
+        assert_eq!(
+            family["themes"][1]["style"]["syntax"]["comment"]["font_style"],
+            "italic"
+        );
+
+        let kept = existing_entry(&content, ThemeMode::Light).unwrap();
+        assert_eq!(kept["style"]["editor.background"], "#f8f9ff");
+        assert!(existing_entry("not json", ThemeMode::Light).is_none());
+    }
+}
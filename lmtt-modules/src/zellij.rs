@@ -0,0 +1,193 @@
+use crate::terminal;
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::PathBuf;
+
+crate::register_module!(ZellijModule);
+
+pub struct ZellijModule;
+
+impl Default for ZellijModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZellijModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// First release that live-reloads config.kdl edits into running sessions.
+const LIVE_RELOAD_VERSION: (u32, u32) = (0, 41);
+
+const INCLUDE_LINE: &str = "theme \"lmtt\"";
+
+fn zellij_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or(lmtt_core::Error::Config("No config dir".into()))?
+        .join("zellij"))
+}
+
+/// A theme in the fg/bg + ANSI-color form every zellij release understands.
+/// Zellij draws its UI from these slots: green is the active frame and
+/// selected tab, so the hue slots come from the shared terminal palette and
+/// the accents from the scheme.
+fn theme_kdl(scheme: &ColorScheme) -> String {
+    let term = terminal::term_colors(scheme);
+    let mut content = String::new();
+    content.push_str("// Zellij theme generated by lmtt\n");
+    content.push_str(&format!("// Mode: {}\n", scheme.mode));
+    content.push_str("themes {\n    lmtt {\n");
+    for (key, value) in [
+        ("fg", term.foreground.clone()),
+        ("bg", scheme.get_or_fallback("surface_container_high")),
+        ("black", term.background.clone()),
+        ("red", term.ansi[1].clone()),
+        ("green", term.ansi[2].clone()),
+        ("yellow", term.ansi[3].clone()),
+        ("blue", term.ansi[4].clone()),
+        ("magenta", term.ansi[5].clone()),
+        ("cyan", term.ansi[6].clone()),
+        ("white", term.ansi[7].clone()),
+        ("orange", scheme.get_or_fallback("tertiary")),
+    ] {
+        content.push_str(&format!("        {} \"{}\"\n", key, value));
+    }
+    content.push_str("    }\n}\n");
+    content
+}
+
+/// Parse `zellij --version` ("zellij 0.41.2") into (major, minor).
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("zellij")?;
+    let mut parts = version.trim().split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+async fn supports_live_reload() -> bool {
+    let Ok(output) = tokio::process::Command::new("zellij")
+        .arg("--version")
+        .output()
+        .await
+    else {
+        return false;
+    };
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .is_some_and(|version| version >= LIVE_RELOAD_VERSION)
+}
+
+/// Names of running sessions. `--short` prints one name per line;
+/// `--no-formatting` drops the ANSI styling. Exited (resurrectable)
+/// sessions only show up in the long listing, so they are excluded here.
+async fn list_sessions() -> Vec<String> {
+    let Ok(output) = tokio::process::Command::new("zellij")
+        .args(["list-sessions", "--short", "--no-formatting"])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    // "No active zellij sessions found." exits non-zero
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[async_trait]
+impl ThemeModule for ZellijModule {
+    fn name(&self) -> &'static str {
+        "zellij"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "zellij"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = zellij_dir()?;
+        let themes_dir = dir.join("themes");
+        tokio::fs::create_dir_all(&themes_dir).await?;
+        let theme_file = themes_dir.join("lmtt.kdl");
+        lmtt_core::fsutil::write_atomic(&theme_file, theme_kdl(scheme)).await?;
+        tracing::info!("[Zellij] Updated theme at {}", theme_file.display());
+
+        if !crate::process_running("zellij").await {
+            return Ok(());
+        }
+        let sessions = list_sessions().await;
+        if sessions.is_empty() {
+            return Ok(());
+        }
+        // Zellij has no command to reload a session's theme, but from 0.41
+        // every session re-reads config.kdl when it changes. Rewriting the
+        // file unchanged (in place, so the watch sees a modify) reloads them
+        // all with the new theme file.
+        let config_kdl = dir.join("config.kdl");
+        let content = tokio::fs::read_to_string(&config_kdl)
+            .await
+            .unwrap_or_default();
+        if !crate::is_included(&content, INCLUDE_LINE) {
+            tracing::debug!("[Zellij] theme \"lmtt\" not set up; run `lmtt setup`");
+            return Ok(());
+        }
+        if !supports_live_reload().await {
+            tracing::info!(
+                "[Zellij] zellij before 0.41 can't reload running sessions; {} keep the old colors until restarted",
+                sessions.join(", ")
+            );
+            return Ok(());
+        }
+        tokio::fs::write(&config_kdl, content).await?;
+        for session in &sessions {
+            tracing::info!("[Zellij] Reloaded theme in session {}", session);
+        }
+
+        Ok(())
+    }
+
+    /// Zellij uses the last `theme` node in config.kdl, so a line at the top
+    /// would lose to one the user already has.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let config_kdl = zellij_dir()?.join("config.kdl");
+        if !config_kdl.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = tokio::fs::read_to_string(&config_kdl).await?;
+        let already_included = crate::is_included(&content, INCLUDE_LINE);
+
+        Ok(vec![ConfigFileInfo {
+            path: config_kdl,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Use the LMTT theme in zellij config.kdl".to_string(),
+            already_included,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(parse_version("zellij 0.41.2\n"), Some((0, 41)));
+        assert!(parse_version("zellij 0.40.1").unwrap() < LIVE_RELOAD_VERSION);
+        assert_eq!(parse_version("zellij: command not found"), None);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::shell::{exports, fzf_opts, path_quote, shell_dir, stamp, STAMP_PREFIX};
+use crate::{ConfigFileInfo, ThemeModule};
+use async_trait::async_trait;
+use lmtt_core::{ColorScheme, Config, Result};
+use std::path::{Path, PathBuf};
+
+crate::register_module!(ZshModule);
+
+pub struct ZshModule;
+
+impl Default for ZshModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZshModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const INCLUDE_LINE: &str =
+    "[ -r ~/.config/lmtt/shell/colors.zsh ] && source ~/.config/lmtt/shell/colors.zsh";
+
+/// `$ZDOTDIR/.zshrc`, else `~/.zshrc`.
+fn zshrc() -> Option<PathBuf> {
+    let dir = std::env::var_os("ZDOTDIR")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)?;
+    Some(dir.join(".zshrc"))
+}
+
+/// zsh-syntax-highlighting styles, with the same roles as fish's own
+/// `fish_color_*` variables.
+fn highlight_styles(scheme: &ColorScheme) -> String {
+    let c = |key: &str| scheme.get_or_fallback(key);
+    let primary = c("primary");
+    let secondary = c("secondary");
+    let tertiary = c("tertiary");
+    let on_surface = c("on_surface");
+    let on_surface_variant = c("on_surface_variant");
+    let outline = c("outline");
+    let error = c("error");
+
+    let styles = [
+        ("default", format!("fg={}", on_surface)),
+        ("unknown-token", format!("fg={},bold", error)),
+        ("reserved-word", format!("fg={}", primary)),
+        ("alias", format!("fg={}", primary)),
+        ("suffix-alias", format!("fg={},underline", primary)),
+        ("global-alias", format!("fg={}", primary)),
+        ("builtin", format!("fg={}", primary)),
+        ("function", format!("fg={}", primary)),
+        ("command", format!("fg={}", primary)),
+        ("hashed-command", format!("fg={}", primary)),
+        ("precommand", format!("fg={},italic", primary)),
+        ("arg0", format!("fg={}", primary)),
+        ("commandseparator", format!("fg={}", primary)),
+        ("autodirectory", format!("fg={},underline", primary)),
+        ("path", format!("fg={},underline", on_surface)),
+        ("globbing", format!("fg={}", tertiary)),
+        ("history-expansion", format!("fg={}", tertiary)),
+        ("single-hyphen-option", format!("fg={}", on_surface_variant)),
+        ("double-hyphen-option", format!("fg={}", on_surface_variant)),
+        ("single-quoted-argument", format!("fg={}", secondary)),
+        ("double-quoted-argument", format!("fg={}", secondary)),
+        ("dollar-quoted-argument", format!("fg={}", secondary)),
+        ("back-quoted-argument", format!("fg={}", tertiary)),
+        ("dollar-double-quoted-argument", format!("fg={}", tertiary)),
+        ("back-double-quoted-argument", format!("fg={}", tertiary)),
+        ("back-dollar-quoted-argument", format!("fg={}", tertiary)),
+        ("redirection", format!("fg={}", secondary)),
+        ("comment", format!("fg={}", outline)),
+    ];
+
+    // Set before or after the plugin loads: it only fills in unset styles.
+    let mut content = String::from("typeset -gA ZSH_HIGHLIGHT_STYLES\n");
+    for (key, style) in styles {
+        content.push_str(&format!("ZSH_HIGHLIGHT_STYLES[{}]='{}'\n", key, style));
+    }
+    content.push_str(&format!(
+        "typeset -g ZSH_AUTOSUGGEST_HIGHLIGHT_STYLE='fg={}'\n",
+        outline
+    ));
+    content
+}
+
+/// Re-source `colors_file` from a precmd hook whenever its stamp differs
+/// from the one last sourced. add-zsh-hook skips a hook already added.
+fn reload_hook(colors_file: &Path) -> String {
+    format!(
+        "_lmtt_precmd() {{\n    \
+             local stamp\n    \
+             IFS= read -r stamp 2>/dev/null < {colors} || return 0\n    \
+             [[ $stamp == \"{prefix}$_lmtt_stamp\" ]] || source {colors}\n\
+         }}\n\
+         autoload -Uz add-zsh-hook\n\
+         add-zsh-hook precmd _lmtt_precmd\n",
+        prefix = STAMP_PREFIX,
+        colors = path_quote(colors_file),
+    )
+}
+
+fn colors_zsh(scheme: &ColorScheme, colors_file: &Path) -> Result<String> {
+    let mut body = exports(scheme, "Zsh")?;
+    body.push('\n');
+    body.push_str(&fzf_opts(scheme));
+    body.push('\n');
+    body.push_str(&highlight_styles(scheme));
+    body.push_str("\n# Running shells pick up a switch at their next prompt\n");
+    body.push_str(&reload_hook(colors_file));
+    let stamp = stamp(&body);
+    // -g: the hook sources this file from inside a function
+    Ok(format!(
+        "{STAMP_PREFIX}{stamp}\n{body}typeset -g _lmtt_stamp={stamp}\n"
+    ))
+}
+
+#[async_trait]
+impl ThemeModule for ZshModule {
+    fn name(&self) -> &'static str {
+        "zsh"
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "zsh"
+    }
+
+    async fn apply(&self, scheme: &ColorScheme, _config: &Config) -> Result<()> {
+        let dir = shell_dir()?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let colors_file = dir.join("colors.zsh");
+        let content = colors_zsh(scheme, &colors_file)?;
+        lmtt_core::fsutil::write_atomic(&colors_file, content).await?;
+
+        tracing::info!("[Zsh] Updated colors at {}", colors_file.display());
+        Ok(())
+    }
+
+    async fn config_files(&self) -> Result<Vec<ConfigFileInfo>> {
+        let Some(zshrc) = zshrc().filter(|path| path.exists()) else {
+            return Ok(vec![]);
+        };
+
+        let content = tokio::fs::read_to_string(&zshrc).await?;
+        let already_included = crate::is_included(&content, INCLUDE_LINE);
+
+        Ok(vec![ConfigFileInfo {
+            path: zshrc,
+            include_line: INCLUDE_LINE.to_string(),
+            description: "Source lmtt colors in .zshrc".to_string(),
+            already_included,
+        }])
+    }
+
+    /// After the user's own FZF_DEFAULT_OPTS, which it extends, and after
+    /// plugin managers that may reset highlight styles.
+    fn include_at_end(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_file_exports_tokens_and_hooks_precmd() {
+        let mut scheme = ColorScheme::new(lmtt_core::ThemeMode::Dark);
+        scheme.set("primary".into(), "#9ccaff".into());
+        let colors_file = Path::new("/home/u/.config/lmtt/shell/colors.zsh");
+        let content = colors_zsh(&scheme, colors_file).unwrap();
+        assert!(content.contains("export LMTT_MODE=dark\nexport LMTT_PRIMARY='#9ccaff'\n"));
+        assert!(content.contains("ZSH_HIGHLIGHT_STYLES[command]='fg=#9ccaff'\n"));
+        assert!(content.contains("--color=fg:"));
+        assert!(content.contains("|| source '/home/u/.config/lmtt/shell/colors.zsh'\n}\n"));
+        assert!(content.contains("add-zsh-hook precmd _lmtt_precmd\ntypeset -g _lmtt_stamp="));
+        let stamp = content.lines().next().unwrap();
+        assert!(stamp.starts_with(STAMP_PREFIX));
+        assert!(content.ends_with(&format!("_lmtt_stamp={}\n", &stamp[STAMP_PREFIX.len()..])));
+    }
+}
@@ -0,0 +1,432 @@
+use anyhow::Result;
+use lmtt_core::Config;
+use lmtt_modules::ModuleRegistry;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Portal backends that implement `org.freedesktop.impl.portal.Settings`,
+/// the interface that carries color-scheme and accent-color. Compositor
+/// backends (hyprland, wlr) only provide screencast/screenshot.
+const SETTINGS_BACKENDS: &[&str] = &["gtk", "gnome", "kde", "darkman"];
+
+/// Bus-name prefix every portal backend registers under.
+const PORTAL_BACKEND_PREFIX: &str = "org.freedesktop.impl.portal.desktop.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Finding {
+    level: Level,
+    check: String,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &str, detail: impl Into<String>) -> Self {
+        Self {
+            level: Level::Ok,
+            check: check.to_string(),
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(check: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            level: Level::Warn,
+            check: check.to_string(),
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(check: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            level: Level::Fail,
+            check: check.to_string(),
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn print(&self) {
+        let icon = match self.level {
+            Level::Ok => "✓",
+            Level::Warn => "!",
+            Level::Fail => "✗",
+        };
+        if self.detail.is_empty() {
+            println!("  {} {}", icon, self.check);
+        } else {
+            println!("  {} {}: {}", icon, self.check, self.detail);
+        }
+        if let Some(fix) = &self.fix {
+            println!("      fix: {}", fix);
+        }
+    }
+}
+
+/// `lmtt doctor`: check the environment a switch depends on, then run every
+/// enabled module's health check. Exits 1 when anything failed; warnings
+/// (degraded but working) don't affect the exit status.
+pub async fn run() -> Result<ExitCode> {
+    let mut env = Vec::new();
+
+    let config = match Config::load() {
+        Ok(config) => {
+            let path = Config::config_path()?;
+            let detail = if path.exists() {
+                path.display().to_string()
+            } else {
+                "no config file, using defaults".to_string()
+            };
+            env.push(Finding::ok("config", detail));
+            config
+        }
+        Err(e) => {
+            env.push(Finding::fail(
+                "config",
+                e.to_string(),
+                format!(
+                    "fix the error in {} (or move it aside and run `lmtt init`)",
+                    Config::config_path()?.display()
+                ),
+            ));
+            Config::default()
+        }
+    };
+
+    env.push(check_matugen(&config).await);
+    if config.general.use_matugen {
+        env.push(check_wallpaper(Path::new(&config.general.wallpaper)));
+    }
+
+    match zbus::Connection::session().await {
+        Ok(conn) => {
+            env.push(Finding::ok("session bus", ""));
+            env.extend(check_portal(&conn).await);
+        }
+        Err(e) => env.push(Finding::fail(
+            "session bus",
+            e.to_string(),
+            "run lmtt inside your graphical session; DBUS_SESSION_BUS_ADDRESS must be set",
+        )),
+    }
+
+    if let Some(finding) = check_qt_env(&config).await {
+        env.push(finding);
+    }
+    if let Some(finding) = check_hyprland_autoreload(&config).await {
+        env.push(finding);
+    }
+    env.push(check_published_dir());
+
+    let mut modules = Vec::new();
+    let registry = ModuleRegistry::new();
+    for module in &registry.modules {
+        if !module.is_enabled(&config) {
+            continue;
+        }
+        modules.push(match module.health_check().await {
+            Ok(()) => check_includes(module.as_ref()).await,
+            // Health-check messages name their own fix.
+            Err(e) => Finding {
+                level: Level::Fail,
+                check: module.name().to_string(),
+                detail: match e {
+                    lmtt_core::Error::Module(msg) => msg,
+                    e => e.to_string(),
+                },
+                fix: None,
+            },
+        });
+    }
+
+    println!("Environment:");
+    env.iter().for_each(Finding::print);
+    println!("\nModules:");
+    if modules.is_empty() {
+        println!("  (no enabled modules are installed)");
+    }
+    modules.iter().for_each(Finding::print);
+
+    let all = env.iter().chain(modules.iter());
+    let failures = all.clone().filter(|f| f.level == Level::Fail).count();
+    let warnings = all.filter(|f| f.level == Level::Warn).count();
+    println!("\n{} problem(s), {} warning(s)", failures, warnings);
+
+    Ok(if failures > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// A config file without lmtt's include still works, it just isn't themed:
+/// the user may have configured the app by hand or left it out of setup on
+/// purpose, so this is a warning rather than a failure.
+async fn check_includes(module: &dyn lmtt_modules::ThemeModule) -> Finding {
+    let files = match module.config_files().await {
+        Ok(files) => files,
+        Err(e) => {
+            return Finding::warn(
+                module.name(),
+                format!("could not inspect its config files: {}", e),
+                "check the permissions of the files `lmtt setup` edits",
+            )
+        }
+    };
+    let missing: Vec<_> = files
+        .iter()
+        .filter(|f| !f.already_included)
+        .map(|f| f.path.display().to_string())
+        .collect();
+    if missing.is_empty() {
+        return Finding::ok(module.name(), "");
+    }
+    Finding::warn(
+        module.name(),
+        format!("{} does not include lmtt's colors", missing.join(", ")),
+        "run `lmtt setup`",
+    )
+}
+
+async fn check_matugen(config: &Config) -> Finding {
+    if !config.general.use_matugen {
+        return Finding::ok("matugen", "disabled (general.use_matugen = false)");
+    }
+    if which::which("matugen").is_err() {
+        return Finding::warn(
+            "matugen",
+            "not found in PATH; switches fall back to default colors",
+            "install matugen, or set general.use_matugen = false to silence this",
+        );
+    }
+    match tokio::process::Command::new("matugen")
+        .arg("--version")
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => Finding::ok(
+            "matugen",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(output) => Finding::warn(
+            "matugen",
+            format!(
+                "`matugen --version` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            "reinstall matugen",
+        ),
+        Err(e) => Finding::warn(
+            "matugen",
+            format!("could not run matugen: {}", e),
+            "reinstall matugen",
+        ),
+    }
+}
+
+fn check_wallpaper(path: &Path) -> Finding {
+    const FIX: &str = "pick a readable image with `lmtt wallpaper set <path>`";
+    if path.is_dir() {
+        return Finding::fail(
+            "wallpaper",
+            format!("{} is a directory", path.display()),
+            FIX,
+        );
+    }
+    match std::fs::File::open(path) {
+        Ok(_) => Finding::ok("wallpaper", path.display().to_string()),
+        Err(e) => Finding::fail("wallpaper", format!("{}: {}", path.display(), e), FIX),
+    }
+}
+
+async fn check_portal(conn: &zbus::Connection) -> Vec<Finding> {
+    use lmtt_modules::xdg::portal_read_one;
+
+    let mut findings = Vec::new();
+
+    if let Err(e) = portal_read_one(conn, "color-scheme").await {
+        findings.push(Finding::fail(
+            "portal",
+            format!("Settings.ReadOne(color-scheme) failed: {}", e),
+            "install xdg-desktop-portal and xdg-desktop-portal-gtk, then \
+             `systemctl --user restart xdg-desktop-portal`",
+        ));
+        return findings;
+    }
+
+    // Backends are D-Bus activated, so the frontend having just answered
+    // ReadOne means whichever backend serves Settings is now on the bus.
+    let backends: Vec<String> = match conn
+        .call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus"),
+            "ListNames",
+            &(),
+        )
+        .await
+        .and_then(|reply| reply.body().deserialize::<Vec<String>>())
+    {
+        Ok(names) => names
+            .iter()
+            .filter_map(|name| name.strip_prefix(PORTAL_BACKEND_PREFIX))
+            .map(str::to_string)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    if backends
+        .iter()
+        .any(|b| SETTINGS_BACKENDS.contains(&b.as_str()))
+    {
+        findings.push(Finding::ok(
+            "portal backend",
+            format!("xdg-desktop-portal-{}", backends.join(", -")),
+        ));
+    } else {
+        let running = if backends.is_empty() {
+            "none running".to_string()
+        } else {
+            format!("{} (none implements Settings)", backends.join(", "))
+        };
+        findings.push(Finding::warn(
+            "portal backend",
+            running,
+            "install xdg-desktop-portal-gtk and add \
+             `org.freedesktop.impl.portal.Settings=gtk` under [preferred] in \
+             ~/.config/xdg-desktop-portal/portals.conf",
+        ));
+    }
+
+    match portal_read_one(conn, "accent-color").await {
+        Ok(_) => findings.push(Finding::ok("portal accent-color", "exposed")),
+        Err(e) => findings.push(Finding::warn(
+            "portal accent-color",
+            format!(
+                "not exposed ({}); GTK4/libadwaita apps keep their stock accent",
+                e
+            ),
+            "update xdg-desktop-portal-gtk/-gnome to a release that serves accent-color",
+        )),
+    }
+
+    findings
+}
+
+async fn check_qt_env(config: &Config) -> Option<Finding> {
+    if !config.is_module_enabled("qt")
+        || which::which("qt6ct").is_err()
+        || which::which("systemctl").is_err()
+    {
+        return None;
+    }
+    Some(
+        if lmtt_modules::qt::session_env_has_any_platformtheme().await {
+            Finding::ok(
+                "QT_QPA_PLATFORMTHEME",
+                "set in the systemd user environment",
+            )
+        } else {
+            Finding::warn(
+                "QT_QPA_PLATFORMTHEME",
+                "not set in the systemd user environment; Qt apps started before \
+                 the first switch ignore the qt6ct palette",
+                "add `QT_QPA_PLATFORMTHEME=qt6ct` to ~/.config/environment.d/qt.conf \
+                 and log in again",
+            )
+        },
+    )
+}
+
+async fn check_hyprland_autoreload(config: &Config) -> Option<Finding> {
+    if !config.is_module_enabled("hyprland")
+        || std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none()
+        || which::which("hyprctl").is_err()
+    {
+        return None;
+    }
+    Some(if lmtt_modules::hyprland::autoreload_disabled().await {
+        Finding::warn(
+            "hyprland autoreload",
+            "misc:disable_autoreload is on, so every switch forces a full \
+             `hyprctl reload` (monitors are re-applied)",
+            "set `misc { disable_autoreload = false }` in hyprland.conf",
+        )
+    } else {
+        Finding::ok("hyprland autoreload", "on")
+    })
+}
+
+/// Publication writes into the appearance-profiles tree as the user; the
+/// shared parent comes from dist/tmpfiles.d/lmtt.conf.
+fn check_published_dir() -> Finding {
+    const CHECK: &str = "published profile dir";
+    let Ok(user) = std::env::var("USER") else {
+        return Finding::warn(CHECK, "USER is not set", "run lmtt from a login session");
+    };
+    let root = match appearance_profiles::published_profile_path(&user) {
+        Ok(path) => match path.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => return Finding::ok(CHECK, path.display().to_string()),
+        },
+        Err(e) => {
+            return Finding::warn(
+                CHECK,
+                e.to_string(),
+                "check that USER is a plain login name",
+            )
+        }
+    };
+
+    // A missing per-user dir is fine as long as its parent lets us create it
+    // on the next publish.
+    let probe_dir = if root.is_dir() {
+        root.clone()
+    } else {
+        match root.parent() {
+            Some(parent) if parent.is_dir() => parent.to_path_buf(),
+            _ => {
+                return Finding::warn(
+                    CHECK,
+                    format!(
+                        "{} is missing; lock screens decode the wallpaper at runtime",
+                        root.parent().unwrap_or(&root).display()
+                    ),
+                    "sudo systemd-tmpfiles --create /usr/lib/tmpfiles.d/lmtt.conf",
+                )
+            }
+        }
+    };
+
+    match probe_writable(&probe_dir) {
+        Ok(()) => Finding::ok(CHECK, root.display().to_string()),
+        Err(e) => Finding::warn(
+            CHECK,
+            format!("{} is not writable: {}", probe_dir.display(), e),
+            if probe_dir == root {
+                format!("sudo chown -R {} {}", user, root.display())
+            } else {
+                "sudo systemd-tmpfiles --create /usr/lib/tmpfiles.d/lmtt.conf".to_string()
+            },
+        ),
+    }
+}
+
+/// Create and remove a scratch file; access(2)-style permission checks miss
+/// read-only mounts and ACLs.
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".lmtt-doctor-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?;
+    std::fs::remove_file(&probe)
+}
@@ -1,4 +1,8 @@
+mod doctor;
 mod matugen;
+mod restore;
+mod wallpaper;
+mod waybar;
 
 use anyhow::Result;
 use appearance_profiles::{
@@ -10,6 +14,15 @@ use lmtt_core::{Config, ThemeMode};
 use lmtt_modules::{CleanupManager, ModuleRegistry, SetupManager};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// `lmtt switch` exit status when some modules failed but at least one was
+/// updated (the new mode is recorded).
+const EXIT_PARTIAL_FAILURE: u8 = 2;
+
+/// `lmtt switch` exit status when every module failed (the previous mode
+/// stays recorded).
+const EXIT_TOTAL_FAILURE: u8 = 3;
 
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,6 +57,8 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Switch to light or dark theme, or toggle between them
+    ///
+    /// Exits 2 when some modules failed and 3 when all of them did.
     Switch {
         /// Theme mode (light or dark). If omitted, toggles between current theme.
         mode: Option<ThemeMode>,
@@ -51,6 +66,10 @@ enum Commands {
         /// Disable notifications
         #[arg(long)]
         no_notify: bool,
+
+        /// Print a machine-readable JSON report instead of text
+        #[arg(long)]
+        json: bool,
     },
 
     /// Setup mode - configure application config files
@@ -72,15 +91,47 @@ enum Commands {
     },
 
     /// Show current theme status
-    Status,
+    Status {
+        /// Print machine-readable JSON instead of text
+        #[arg(long, conflicts_with = "waybar")]
+        json: bool,
+
+        /// Print a waybar custom-module JSON line (`"return-type": "json"`)
+        #[arg(long)]
+        waybar: bool,
+
+        /// Keep running and print a new waybar line on every state change
+        #[arg(long, requires = "waybar")]
+        follow: bool,
+    },
 
     /// List installed modules
     List {
         /// Show all modules (including not installed)
         #[arg(long)]
         all: bool,
+
+        /// Print machine-readable JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Re-apply the last palette and mode without generating colors
+    ///
+    /// Meant for login: reads tokens.json and theme_state instead of running
+    /// matugen. Exits like `switch`.
+    Restore {
+        /// Install and enable a systemd user service that runs `lmtt restore`
+        /// when the graphical session starts
+        #[arg(long)]
+        install_service: bool,
     },
 
+    /// Diagnose the environment and every enabled module, with fixes
+    ///
+    /// Exits 1 when a check failed.
+    Doctor,
+
     /// Initialize config file
     Init,
 
@@ -155,7 +206,7 @@ impl From<WallpaperFit> for Fit {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
     // Initialize logging: --verbose wins, then RUST_LOG, then config [logging].level
@@ -168,10 +219,14 @@ async fn main() -> Result<()> {
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(log_level));
 
+    // Console logs go to stderr: stdout carries command output, which
+    // `--json` / `--waybar` consumers parse.
     use tracing_subscriber::prelude::*;
-    let registry = tracing_subscriber::registry()
-        .with(env_filter)
-        .with(tracing_subscriber::fmt::layer().with_target(false));
+    let registry = tracing_subscriber::registry().with(env_filter).with(
+        tracing_subscriber::fmt::layer()
+            .with_target(false)
+            .with_writer(std::io::stderr),
+    );
 
     match open_log_file(&logging) {
         Some(file) => registry
@@ -185,8 +240,12 @@ async fn main() -> Result<()> {
     }
 
     match cli.command {
-        Commands::Switch { mode, no_notify } => {
-            cmd_switch(mode, no_notify).await?;
+        Commands::Switch {
+            mode,
+            no_notify,
+            json,
+        } => {
+            return cmd_switch(mode, no_notify, json).await;
         }
 
         Commands::Setup { dry_run } => {
@@ -197,12 +256,40 @@ async fn main() -> Result<()> {
             cmd_cleanup(dry_run, module).await?;
         }
 
-        Commands::Status => {
-            cmd_status().await?;
+        Commands::Status {
+            json,
+            waybar,
+            follow,
+        } => {
+            if follow {
+                waybar::follow().await?;
+            } else if waybar {
+                let config = Config::load()?;
+                let cache = lmtt_core::cache::Cache::from_config(&config)?;
+                let tokens = lmtt_core::tokens::load_current().ok();
+                println!(
+                    "{}",
+                    waybar::render(&config, &cache, tokens.as_ref()).await?
+                );
+            } else {
+                cmd_status(json).await?;
+            }
         }
 
-        Commands::List { all } => {
-            cmd_list(all).await?;
+        Commands::List { all, json } => {
+            cmd_list(all, json).await?;
+        }
+
+        Commands::Restore { install_service } => {
+            if install_service {
+                restore::install_service().await?;
+            } else {
+                return restore::run().await;
+            }
+        }
+
+        Commands::Doctor => {
+            return doctor::run().await;
         }
 
         Commands::Init => {
@@ -210,16 +297,16 @@ async fn main() -> Result<()> {
         }
 
         Commands::Config => {
-            return lmtt_config_tui::run_config_tui();
+            lmtt_config_tui::run_config_tui()?;
         }
-        Commands::Wallpaper { command } => cmd_wallpaper(command)?,
+        Commands::Wallpaper { command } => cmd_wallpaper(command).await?,
         Commands::Tokens { key, user } => cmd_tokens(key, user)?,
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
-fn cmd_wallpaper(command: WallpaperCommand) -> Result<()> {
+async fn cmd_wallpaper(command: WallpaperCommand) -> Result<()> {
     match command {
         WallpaperCommand::Set {
             path,
@@ -247,6 +334,10 @@ fn cmd_wallpaper(command: WallpaperCommand) -> Result<()> {
             if !no_publish {
                 publish_current(&profile)?;
             }
+            let config = Config::load()?;
+            if let Err(error) = wallpaper::apply_current(&config.wallpaper, true).await {
+                eprintln!("Wallpaper not applied to the desktop: {error}");
+            }
         }
         WallpaperCommand::Resolve {
             output,
@@ -328,7 +419,7 @@ fn publish_current(profile: &Profile) -> Result<()> {
 }
 
 fn publish_prepared_bundle(root: &Path, user: &str, snapshot: &Profile) -> Result<()> {
-    let mut monitors = active_monitors().unwrap_or_else(|error| {
+    let mut monitors = active_monitors(true).unwrap_or_else(|error| {
         tracing::debug!("live monitor discovery unavailable: {error}");
         Vec::new()
     });
@@ -415,10 +506,14 @@ fn publish_prepared_bundle(root: &Path, user: &str, snapshot: &Profile) -> Resul
     Ok(())
 }
 
-fn active_monitors() -> Result<Vec<CacheMonitor>> {
-    let output = std::process::Command::new("hyprctl")
-        .args(["-j", "monitors", "all"])
-        .output()?;
+/// Hyprland's monitors. Publishing prepares assets for disabled monitors
+/// too (`monitors all`), so they are ready when the monitor comes back.
+fn active_monitors(include_disabled: bool) -> Result<Vec<CacheMonitor>> {
+    let mut args = vec!["-j", "monitors"];
+    if include_disabled {
+        args.push("all");
+    }
+    let output = std::process::Command::new("hyprctl").args(args).output()?;
     if !output.status.success() {
         anyhow::bail!("hyprctl monitors failed with {}", output.status);
     }
@@ -551,21 +646,26 @@ fn open_log_file(logging: &lmtt_core::config::LoggingConfig) -> Option<std::fs::
 /// other importers parse it as an unknown selector and ignore it.
 const WAYBAR_TRAY_CSS: &str = "\n/* Tray icon theming: prefer symbolic icons recolored by foreground */\n#tray {\n    -gtk-icon-style: symbolic;\n    color: @foreground;\n}\n";
 
-async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
-    let config = Config::load()?;
-    let cache = lmtt_core::cache::Cache::from_config(&config)?;
-
-    // Serialize concurrent switches (e.g. a double-tapped toggle keybind):
-    // without this both processes read the same state and toggle to the same
-    // mode while interleaving writes to shared files. Blocking is correct —
-    // the second invocation then sees the first one's saved state.
-    let lock_path = std::path::PathBuf::from(&config.cache.dir).join("lmtt.lock");
+/// Serialize concurrent switches (e.g. a double-tapped toggle keybind):
+/// without this both processes read the same state and toggle to the same
+/// mode while interleaving writes to shared files. Blocking is correct —
+/// the second invocation then sees the first one's saved state. The lock is
+/// held until the returned file is dropped.
+fn lock_switch(cache: &lmtt_core::cache::Cache) -> Result<std::fs::File> {
     let lock_file = std::fs::OpenOptions::new()
         .create(true)
         .truncate(false)
         .write(true)
-        .open(&lock_path)?;
+        .open(cache.lock_path())?;
     lock_file.lock()?;
+    Ok(lock_file)
+}
+
+async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool, json: bool) -> Result<ExitCode> {
+    let config = Config::load()?;
+    let cache = lmtt_core::cache::Cache::from_config(&config)?;
+
+    let _lock = lock_switch(&cache)?;
 
     let registry = ModuleRegistry::new();
 
@@ -578,11 +678,15 @@ async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
             ThemeMode::Light => ThemeMode::Dark,
             ThemeMode::Dark => ThemeMode::Light,
         };
-        println!("Toggling from {} to {} mode...", current, toggled);
+        if !json {
+            println!("Toggling from {} to {} mode...", current, toggled);
+        }
         toggled
     };
 
-    println!("Switching to {} mode...", mode);
+    if !json {
+        println!("Switching to {} mode...", mode);
+    }
 
     // Generate color scheme
     let color_cache = if config.cache.enabled {
@@ -590,7 +694,13 @@ async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
     } else {
         None
     };
-    let scheme = matugen::generate_colors(&config, mode, color_cache).await?;
+    let (scheme, color_source) = matugen::generate_colors(&config, mode, color_cache).await?;
+    // The palette being replaced, for the transition fade; read it before
+    // write_current overwrites it.
+    let previous = (config.switch.transition_ms > 0)
+        .then(|| lmtt_core::tokens::load_current().ok())
+        .flatten()
+        .filter(|previous| previous.colors != scheme.colors);
     lmtt_core::tokens::write_current(&scheme)?;
     if let Ok(user) = std::env::var("USER") {
         if let Err(error) = lmtt_core::tokens::write_published(&user, &scheme) {
@@ -614,30 +724,55 @@ async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
     css.push_str(WAYBAR_TRAY_CSS);
     lmtt_core::fsutil::write_atomic(&css_path, css).await?;
 
+    // Fade live-recolorable apps first; apply_all then lands the final state
+    // everywhere (including on them).
+    if let Some(previous) = &previous {
+        registry.transition(previous, &scheme, &config).await;
+    }
+
     // Apply to all modules
     let results = registry.apply_all(&scheme, &config).await;
 
     // Print results
     let mut successes = 0;
     let mut failures = 0;
+    let mut failed = Vec::new();
+    let mut report = Vec::new();
 
     let show_progress =
         config.notifications.enabled && !no_notify && config.notifications.show_module_progress;
 
     for result in results {
+        let slow = result.is_slow(config.performance.slow_module_threshold);
         if result.is_success() {
             successes += 1;
-            let icon = if result.is_slow(config.performance.slow_module_threshold) {
-                "⚠"
-            } else {
-                "✓"
-            };
-            println!("{} [{}] {}ms", icon, result.name, result.duration_ms);
-        } else {
+            if !json {
+                let icon = if slow { "⚠" } else { "✓" };
+                println!("{} [{}] {}ms", icon, result.name, result.duration_ms);
+            }
+        } else if let Err(e) = &result.result {
             failures += 1;
-            if let Err(e) = &result.result {
+            if !json {
                 eprintln!("✗ [{}] {}", result.name, e);
             }
+            failed.push(lmtt_core::cache::FailedModule {
+                name: result.name.clone(),
+                error: e.to_string(),
+            });
+        }
+
+        if json {
+            let status = match (&result.result, slow) {
+                (Err(_), _) => "failed",
+                (Ok(()), true) => "slow",
+                (Ok(()), false) => "ok",
+            };
+            report.push(serde_json::json!({
+                "name": result.name,
+                "duration_ms": result.duration_ms,
+                "status": status,
+                "error": result.result.as_ref().err().map(|e| e.to_string()),
+            }));
         }
 
         if show_progress {
@@ -657,17 +792,51 @@ async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
         }
     }
 
-    println!("\n{} successful, {} failed", successes, failures);
+    // The desktop wallpaper follows the appearance-profiles registry. It is
+    // not a module: a failure is reported but never fails the switch.
+    if let Err(error) = wallpaper::apply_current(&config.wallpaper, false).await {
+        tracing::warn!("wallpaper not applied: {error}");
+    }
+
+    let total_failure = successes == 0 && failures > 0;
+
+    let record = lmtt_core::cache::SwitchRecord {
+        mode,
+        succeeded: successes,
+        failed,
+    };
+    if let Err(e) = cache.set_last_switch(&record).await {
+        tracing::debug!("last switch record not written: {}", e);
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "mode": mode,
+                "color_source": color_source,
+                "recorded": !total_failure,
+                "succeeded": successes,
+                "failed": failures,
+                "modules": report,
+            }))?
+        );
+    } else {
+        println!("\n{} successful, {} failed", successes, failures);
+    }
 
-    if successes == 0 && failures > 0 {
+    if total_failure {
         // Nothing switched: don't record a state we never reached, and let
         // scripts see the failure in the exit code.
-        anyhow::bail!("theme switch failed: all {} modules failed", failures);
+        if !json {
+            eprintln!("theme switch failed: all {} modules failed", failures);
+        }
+        return Ok(ExitCode::from(EXIT_TOTAL_FAILURE));
     }
 
     cache.set_theme_state(mode).await?;
 
-    if failures == 0 {
+    if failures == 0 && !json {
         println!("Theme switched to {} mode!", mode);
     }
 
@@ -675,7 +844,10 @@ async fn cmd_switch(mode: Option<ThemeMode>, no_notify: bool) -> Result<()> {
         notify_switch(&config, mode, successes, failures).await;
     }
 
-    Ok(())
+    if failures > 0 {
+        return Ok(ExitCode::from(EXIT_PARTIAL_FAILURE));
+    }
+    Ok(ExitCode::SUCCESS)
 }
 
 /// Best-effort desktop notification; failures are logged, never fatal.
@@ -729,23 +901,65 @@ async fn cmd_cleanup(dry_run: bool, module: Option<String>) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_status() -> Result<()> {
+async fn cmd_status(json: bool) -> Result<()> {
     let config = Config::load()?;
     let cache = lmtt_core::cache::Cache::from_config(&config)?;
 
     let current_mode = cache.get_theme_state(config.general.default_mode).await?;
+    let tokens_path = lmtt_core::tokens::user_tokens_path()?;
+    let last_switch = cache
+        .theme_state_modified()
+        .await
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "mode": current_mode,
+                "wallpaper": config.general.wallpaper,
+                "scheme_type": config.general.scheme_type,
+                "tokens_path": tokens_path.is_file().then_some(&tokens_path),
+                "last_switch": last_switch,
+            }))?
+        );
+        return Ok(());
+    }
 
     println!("Current theme: {}", current_mode);
     println!("Wallpaper: {}", config.general.wallpaper);
     println!("Scheme type: {}", config.general.scheme_type);
+    if tokens_path.is_file() {
+        println!("Tokens: {}", tokens_path.display());
+    }
 
     Ok(())
 }
 
-async fn cmd_list(all: bool) -> Result<()> {
+async fn cmd_list(all: bool, json: bool) -> Result<()> {
     let config = Config::load()?;
     let registry = ModuleRegistry::new();
 
+    if json {
+        let modules: Vec<_> = registry
+            .modules
+            .iter()
+            .filter(|module| all || module.is_enabled(&config))
+            .map(|module| {
+                serde_json::json!({
+                    "name": module.name(),
+                    "installed": module.is_installed(),
+                    "enabled": config.is_module_enabled(module.name()),
+                    "priority": module.priority(),
+                    "source": if module.is_custom() { "custom" } else { "builtin" },
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&modules)?);
+        return Ok(());
+    }
+
     println!("Module Status:");
     println!("==============\n");
 
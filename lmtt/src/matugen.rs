@@ -10,15 +10,17 @@ use std::path::Path;
 /// 3. the built-in fallback palette
 ///
 /// Failures at each step fall through to the next — a broken wallpaper or
-/// malformed JSON must never abort the whole switch.
+/// malformed JSON must never abort the whole switch. The returned
+/// `ColorSource` records which step actually produced the palette.
 pub async fn generate_colors(
     config: &Config,
     mode: ThemeMode,
     cache: Option<&Cache>,
-) -> Result<ColorScheme> {
+) -> Result<(ColorScheme, ColorSource)> {
     let mut scheme = ColorScheme::new(mode);
 
-    for (key, value) in resolve_colors(config, mode, cache).await {
+    let (colors, source) = resolve_colors(config, mode, cache).await;
+    for (key, value) in colors {
         insert_color(&mut scheme, key, value);
     }
 
@@ -27,7 +29,32 @@ pub async fn generate_colors(
         insert_color(&mut scheme, key.clone(), value.clone());
     }
 
-    Ok(scheme)
+    Ok((scheme, source))
+}
+
+/// Where a switch's palette came from, in resolution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSource {
+    /// Fresh matugen run against the wallpaper
+    Matugen,
+    /// Previously generated matugen colors for the same wallpaper/mode/type
+    Cache,
+    /// The configured default_{light,dark}_colors JSON file
+    DefaultColors,
+    /// The built-in fallback palette
+    Fallback,
+}
+
+impl std::fmt::Display for ColorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ColorSource::Matugen => write!(f, "matugen"),
+            ColorSource::Cache => write!(f, "cache"),
+            ColorSource::DefaultColors => write!(f, "default_colors"),
+            ColorSource::Fallback => write!(f, "fallback"),
+        }
+    }
 }
 
 /// Insert a color only if it's a valid hex value. Color values reach shells,
@@ -50,10 +77,10 @@ async fn resolve_colors(
     config: &Config,
     mode: ThemeMode,
     cache: Option<&Cache>,
-) -> std::collections::HashMap<String, String> {
+) -> (std::collections::HashMap<String, String>, ColorSource) {
     if config.general.use_matugen && which::which("matugen").is_ok() {
         match generate_with_matugen(config, mode, cache).await {
-            Ok(colors) => return colors,
+            Ok(generated) => return generated,
             Err(e) => {
                 tracing::warn!(
                     "matugen color generation failed: {}, trying default colors",
@@ -73,7 +100,7 @@ async fn resolve_colors(
         match load_custom_colors(&default_path).await {
             Ok(colors) => {
                 tracing::info!("Using default color scheme from {}", default_path.display());
-                return colors;
+                return (colors, ColorSource::DefaultColors);
             }
             Err(e) => {
                 tracing::warn!("Ignoring default colors {}: {}", default_path.display(), e);
@@ -82,7 +109,7 @@ async fn resolve_colors(
     }
 
     tracing::info!("Using built-in fallback theme");
-    fallback_colors(mode)
+    (fallback_colors(mode), ColorSource::Fallback)
 }
 
 /// Load custom colors from JSON file
@@ -126,7 +153,7 @@ async fn generate_with_matugen(
     config: &Config,
     mode: ThemeMode,
     cache: Option<&Cache>,
-) -> Result<std::collections::HashMap<String, String>> {
+) -> Result<(std::collections::HashMap<String, String>, ColorSource)> {
     let wallpaper = &config.general.wallpaper;
     let scheme_type = &config.general.scheme_type;
     let mode_str = mode.to_string();
@@ -147,7 +174,7 @@ async fn generate_with_matugen(
         {
             Ok(Some(colors)) => {
                 tracing::info!("Using cached colors for {} mode", mode_str);
-                return Ok(colors);
+                return Ok((colors, ColorSource::Cache));
             }
             Ok(None) => {}
             Err(e) => {
@@ -193,5 +220,5 @@ async fn generate_with_matugen(
         }
     }
 
-    Ok(colors)
+    Ok((colors, ColorSource::Matugen))
 }
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use lmtt_core::cache::Cache;
+use lmtt_core::Config;
+use lmtt_modules::ModuleRegistry;
+use std::process::ExitCode;
+
+const SERVICE_NAME: &str = "lmtt-restore.service";
+
+/// `lmtt restore`: re-apply the palette from the last switch (tokens.json) in
+/// the recorded mode (theme_state). Nothing is generated, published or
+/// recorded, so it costs only the module applies and the wallpaper. Built-in
+/// modules check `process_running` before signalling or restarting an app,
+/// so apps that aren't up yet only get their files written; custom modules
+/// do the same when they set `process`. Falls back to a full switch when the
+/// tokens are missing or belong to the other mode (the last switch failed
+/// before recording its state).
+pub async fn run() -> Result<ExitCode> {
+    let config = Config::load()?;
+    let cache = Cache::from_config(&config)?;
+    let mode = cache.get_theme_state(config.general.default_mode).await?;
+
+    let lock = crate::lock_switch(&cache)?;
+    let scheme = match lmtt_core::tokens::load_current() {
+        Ok(scheme) if scheme.mode == mode => scheme,
+        Ok(scheme) => {
+            tracing::warn!(
+                "tokens.json is for {} mode but the recorded mode is {}; running a full switch",
+                scheme.mode,
+                mode
+            );
+            drop(lock);
+            return crate::cmd_switch(Some(mode), true, false).await;
+        }
+        Err(e) => {
+            tracing::warn!("No usable tokens.json ({}); running a full switch", e);
+            drop(lock);
+            return crate::cmd_switch(Some(mode), true, false).await;
+        }
+    };
+
+    println!("Restoring {} mode...", mode);
+
+    let results = ModuleRegistry::new().apply_all(&scheme, &config).await;
+
+    let mut successes = 0;
+    let mut failures = 0;
+    for result in results {
+        match &result.result {
+            Ok(()) => {
+                successes += 1;
+                println!("✓ [{}] {}ms", result.name, result.duration_ms);
+            }
+            Err(e) => {
+                failures += 1;
+                eprintln!("✗ [{}] {}", result.name, e);
+            }
+        }
+    }
+    println!("\n{} successful, {} failed", successes, failures);
+
+    // As in a switch, a wallpaper failure is reported but never fails restore
+    if let Err(error) = crate::wallpaper::apply_current(&config.wallpaper, false).await {
+        tracing::warn!("wallpaper not applied: {error}");
+    }
+
+    if successes == 0 && failures > 0 {
+        return Ok(ExitCode::from(crate::EXIT_TOTAL_FAILURE));
+    }
+    if failures > 0 {
+        return Ok(ExitCode::from(crate::EXIT_PARTIAL_FAILURE));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Write `~/.config/systemd/user/lmtt-restore.service` pointing at this
+/// binary and enable it for graphical-session.target.
+pub async fn install_service() -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let unit_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("cannot determine the user config directory"))?
+        .join("systemd")
+        .join("user");
+    tokio::fs::create_dir_all(&unit_dir).await?;
+
+    let unit_path = unit_dir.join(SERVICE_NAME);
+    lmtt_core::fsutil::write_atomic(&unit_path, service_unit(&exe.to_string_lossy())).await?;
+    println!("Wrote {}", unit_path.display());
+
+    for args in [
+        vec!["--user", "daemon-reload"],
+        vec!["--user", "enable", SERVICE_NAME],
+    ] {
+        let status = tokio::process::Command::new("systemctl")
+            .args(&args)
+            .status()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to run systemctl: {}", e))?;
+        if !status.success() {
+            anyhow::bail!("systemctl {} failed ({})", args.join(" "), status);
+        }
+    }
+
+    println!("Enabled {} for graphical-session.target", SERVICE_NAME);
+    println!("Remove any `lmtt switch` from your compositor's autostart.");
+    Ok(())
+}
+
+fn service_unit(exe: &str) -> String {
+    // systemd splits ExecStart on whitespace unless the word is quoted
+    let exe = if exe.contains(char::is_whitespace) {
+        format!("\"{}\"", exe)
+    } else {
+        exe.to_string()
+    };
+    format!(
+        "# generated by `lmtt restore --install-service`\n\
+         [Unit]\n\
+         Description=Restore the lmtt theme for the graphical session\n\
+         PartOf=graphical-session.target\n\
+         After=graphical-session.target\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         ExecStart={exe} restore\n\
+         \n\
+         [Install]\n\
+         WantedBy=graphical-session.target\n"
+    )
+}
@@ -0,0 +1,688 @@
+use anyhow::Result;
+use appearance_profiles::{Fit, OutputIdentity, Registry};
+use lmtt_core::config::{WallpaperBackend, WallpaperConfig};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+/// The daemon a wallpaper is pushed to, once `auto` has been resolved.
+#[derive(Clone, Copy)]
+enum Backend {
+    Swww,
+    Swaybg,
+    Hyprpaper,
+}
+
+impl Backend {
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Swww => "swww",
+            Backend::Swaybg => "swaybg",
+            Backend::Hyprpaper => "hyprpaper",
+        }
+    }
+}
+
+/// A live output, with the "make model serial" description that
+/// appearance-profiles `desc:` rules match.
+struct LiveOutput {
+    name: String,
+    description: Option<String>,
+}
+
+/// One live output and the wallpaper the registry resolves for it.
+struct Target {
+    output: String,
+    path: PathBuf,
+    fit: Fit,
+}
+
+#[derive(Deserialize)]
+struct SwayOutput {
+    name: String,
+    #[serde(default)]
+    make: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    serial: String,
+    #[serde(default)]
+    active: bool,
+}
+
+#[derive(Deserialize)]
+struct NiriOutput {
+    name: String,
+    #[serde(default)]
+    make: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    serial: Option<String>,
+    /// Absent for disabled outputs.
+    #[serde(default)]
+    logical: Option<serde_json::Value>,
+}
+
+/// Hyprland's description format: make, model and serial, skipping blanks.
+fn describe(make: &str, model: &str, serial: &str) -> Option<String> {
+    let parts: Vec<&str> = [make, model, serial]
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+async fn command_json(program: &str, args: &[&str]) -> Result<Vec<u8>> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} {} failed with {}",
+            program,
+            args.join(" "),
+            output.status
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Enabled outputs of the running compositor: Hyprland, sway or niri.
+async fn live_outputs() -> Result<Vec<LiveOutput>> {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        // Shared with the sync publish path, so run off the async workers
+        let monitors = tokio::task::spawn_blocking(|| crate::active_monitors(false)).await??;
+        return Ok(monitors
+            .into_iter()
+            .map(|monitor| LiveOutput {
+                description: monitor
+                    .selectors
+                    .iter()
+                    .find_map(|selector| selector.strip_prefix("desc:").map(str::to_owned)),
+                name: monitor.name,
+            })
+            .collect());
+    }
+
+    if std::env::var_os("SWAYSOCK").is_some() {
+        let outputs: Vec<SwayOutput> =
+            serde_json::from_slice(&command_json("swaymsg", &["-t", "get_outputs", "-r"]).await?)?;
+        return Ok(outputs
+            .into_iter()
+            .filter(|output| output.active)
+            .map(|output| LiveOutput {
+                description: describe(&output.make, &output.model, &output.serial),
+                name: output.name,
+            })
+            .collect());
+    }
+
+    if std::env::var_os("NIRI_SOCKET").is_some() {
+        let outputs: HashMap<String, NiriOutput> =
+            serde_json::from_slice(&command_json("niri", &["msg", "-j", "outputs"]).await?)?;
+        return Ok(outputs
+            .into_values()
+            .filter(|output| output.logical.is_some())
+            .map(|output| LiveOutput {
+                description: describe(
+                    &output.make,
+                    &output.model,
+                    output.serial.as_deref().unwrap_or_default(),
+                ),
+                name: output.name,
+            })
+            .collect());
+    }
+
+    anyhow::bail!("no Hyprland, sway or niri session to list outputs from")
+}
+
+/// Resolve every live output against the current user's registry. Outputs
+/// with no configured wallpaper are left alone.
+fn resolve_targets(outputs: &[LiveOutput]) -> Result<Vec<Target>> {
+    let registry = Registry::load_current_user()?;
+    let mut targets: Vec<Target> = outputs
+        .iter()
+        .filter_map(|output| {
+            let identity = OutputIdentity::new(output.name.clone(), output.description.clone());
+            let resolved = registry.resolve(&identity, None);
+            Some(Target {
+                output: output.name.clone(),
+                path: resolved.path?,
+                fit: resolved.fit,
+            })
+        })
+        .collect();
+    targets.sort_by(|a, b| a.output.cmp(&b.output));
+    Ok(targets)
+}
+
+/// The configured backend, or for `auto` the first daemon found running.
+/// None means the wallpaper is left to the desktop.
+async fn select_backend(config: &WallpaperConfig) -> Option<Backend> {
+    match config.backend {
+        WallpaperBackend::Auto => {
+            for (daemon, backend) in [
+                ("swww-daemon", Backend::Swww),
+                ("hyprpaper", Backend::Hyprpaper),
+                ("swaybg", Backend::Swaybg),
+            ] {
+                if lmtt_modules::process_running(daemon).await {
+                    return Some(backend);
+                }
+            }
+            None
+        }
+        WallpaperBackend::Swww => Some(Backend::Swww),
+        WallpaperBackend::Swaybg => Some(Backend::Swaybg),
+        WallpaperBackend::Hyprpaper => Some(Backend::Hyprpaper),
+        WallpaperBackend::None => None,
+    }
+}
+
+/// swww `--resize`: crop covers the output, fit letterboxes, no keeps the
+/// image at its own size, centered. swww can neither stretch nor tile.
+fn swww_resize(fit: &Fit) -> &'static str {
+    match fit {
+        Fit::Fill | Fit::Stretch => "crop",
+        Fit::Fit => "fit",
+        Fit::Center | Fit::Tile => "no",
+    }
+}
+
+/// swaybg `-m` has every fit mode.
+fn swaybg_mode(fit: &Fit) -> &'static str {
+    match fit {
+        Fit::Fill => "fill",
+        Fit::Fit => "fit",
+        Fit::Stretch => "stretch",
+        Fit::Center => "center",
+        Fit::Tile => "tile",
+    }
+}
+
+/// hyprpaper covers the output unless the path carries a `contain:` or
+/// `tile:` prefix. It can't stretch, and contain is the closest to center.
+fn hyprpaper_prefix(fit: &Fit) -> &'static str {
+    match fit {
+        Fit::Fill | Fit::Stretch => "",
+        Fit::Fit | Fit::Center => "contain:",
+        Fit::Tile => "tile:",
+    }
+}
+
+async fn swww_ready() -> bool {
+    tokio::process::Command::new("swww")
+        .arg("query")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .is_ok_and(|status| status.success())
+}
+
+/// Start swww-daemon when swww was picked explicitly but isn't running.
+async fn ensure_swww_daemon() -> Result<()> {
+    if swww_ready().await {
+        return Ok(());
+    }
+    tokio::process::Command::new("swww-daemon")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("failed to start swww-daemon: {}", e))?;
+
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(2);
+    while tokio::time::Instant::now() < deadline {
+        if swww_ready().await {
+            return Ok(());
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    }
+    anyhow::bail!("swww-daemon did not come up")
+}
+
+/// Image per output from `swww query` ("DP-1: 2560x1440, scale: 1,
+/// currently displaying: image: /path"). Outputs showing a plain color are
+/// left out.
+fn parse_swww_query(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (output, rest) = line.trim_start_matches(':').trim().split_once(':')?;
+            let (_, image) = rest.split_once("image: ")?;
+            Some((output.trim().to_owned(), image.trim().to_owned()))
+        })
+        .collect()
+}
+
+async fn swww_displayed() -> HashMap<String, String> {
+    let Ok(output) = tokio::process::Command::new("swww")
+        .arg("query")
+        .output()
+        .await
+    else {
+        return HashMap::new();
+    };
+    parse_swww_query(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `swww img` per output, skipping outputs already showing the image unless
+/// `force`: swww doesn't report the resize mode, so only an explicit
+/// `lmtt wallpaper set` re-sends an unchanged image. Returns the number of
+/// outputs updated.
+async fn apply_swww(targets: &[Target], config: &WallpaperConfig, force: bool) -> Result<usize> {
+    ensure_swww_daemon().await?;
+    let displayed = if force {
+        HashMap::new()
+    } else {
+        swww_displayed().await
+    };
+    let mut updated = 0;
+    let mut failed = Vec::new();
+    for target in targets {
+        if displayed.get(&target.output).map(String::as_str) == target.path.to_str() {
+            continue;
+        }
+        updated += 1;
+        let output = tokio::process::Command::new("swww")
+            .arg("img")
+            .arg(&target.path)
+            .args(["--outputs", &target.output])
+            .args(["--resize", swww_resize(&target.fit)])
+            .args(["--transition-type", &config.transition_type])
+            .args([
+                "--transition-duration",
+                &config.transition_duration.to_string(),
+            ])
+            .args(["--transition-fps", &config.transition_fps.to_string()])
+            .output()
+            .await?;
+        if !output.status.success() {
+            failed.push(format!(
+                "{}: {}",
+                target.output,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("swww rejected wallpapers for {}", failed.join("; "));
+    }
+    Ok(updated)
+}
+
+async fn swaybg_pids() -> Vec<String> {
+    let Ok(output) = tokio::process::Command::new("pgrep")
+        .args(["-x", "swaybg"])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The options a swaybg command line gives each output: those after
+/// `-o NAME` apply to that output, earlier ones to every output (`*`).
+fn swaybg_outputs(args: &[String]) -> HashMap<String, Vec<String>> {
+    let mut outputs: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current = "*".to_string();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                if let Some(name) = args.next() {
+                    current = name.clone();
+                }
+            }
+            "-i" | "--image" | "-m" | "--mode" | "-c" | "--color" => {
+                if let Some(value) = args.next() {
+                    outputs
+                        .entry(current.clone())
+                        .or_default()
+                        .extend([arg.clone(), value.clone()]);
+                }
+            }
+            _ => {}
+        }
+    }
+    outputs
+}
+
+/// The arguments a running swaybg was started with.
+fn swaybg_cmdline(pid: &str) -> Option<Vec<String>> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    Some(
+        cmdline
+            .split(|&b| b == 0)
+            .skip(1)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+/// What the running swaybg instances show on each output, from their
+/// command lines. Options from several instances add up rather than replace
+/// each other.
+fn previous_swaybg(instances: &[Vec<String>]) -> HashMap<String, Vec<String>> {
+    let mut outputs: HashMap<String, Vec<String>> = HashMap::new();
+    for args in instances {
+        for (output, options) in swaybg_outputs(args) {
+            outputs.entry(output).or_default().extend(options);
+        }
+    }
+    outputs
+}
+
+/// Whether a running swaybg's options for an output (see `carried_options`)
+/// already show the target's image and mode. The last of a repeated option
+/// is the one swaybg uses.
+fn swaybg_shows(options: &[String], target: &Target) -> bool {
+    let last = |names: [&str; 2]| {
+        options
+            .chunks(2)
+            .rev()
+            .filter(|pair| names.contains(&pair[0].as_str()))
+            .find_map(|pair| pair.get(1))
+    };
+    last(["-i", "--image"]).map(PathBuf::from).as_ref() == Some(&target.path)
+        && last(["-m", "--mode"]).map(String::as_str) == Some(swaybg_mode(&target.fit))
+}
+
+/// The options to carry over for one output: the global (`*`) ones first,
+/// then its own, so its own win where both set the same thing.
+fn carried_options(previous: &HashMap<String, Vec<String>>, output: &str) -> Vec<String> {
+    [previous.get("*"), previous.get(output)]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// swaybg has no IPC: start one instance covering every output, then stop
+/// the old ones once it is up, so there is no bare-desktop flash between.
+/// Outputs without a configured wallpaper keep what the old instances showed
+/// on them. Unless `force`, nothing is restarted when every target already
+/// shows its image and mode. Returns the number of outputs updated.
+async fn apply_swaybg(targets: &[Target], outputs: &[LiveOutput], force: bool) -> Result<usize> {
+    let old = swaybg_pids().await;
+    let instances: Vec<Vec<String>> = old.iter().filter_map(|pid| swaybg_cmdline(pid)).collect();
+    let previous = previous_swaybg(&instances);
+    if !force
+        && targets
+            .iter()
+            .all(|target| swaybg_shows(&carried_options(&previous, &target.output), target))
+    {
+        return Ok(0);
+    }
+
+    let mut command = tokio::process::Command::new("swaybg");
+    for target in targets {
+        command
+            .args(["-o", &target.output, "-i"])
+            .arg(&target.path)
+            .args(["-m", swaybg_mode(&target.fit)]);
+    }
+    for output in outputs {
+        if targets.iter().any(|target| target.output == output.name) {
+            continue;
+        }
+        let options = carried_options(&previous, &output.name);
+        if !options.is_empty() {
+            command.args(["-o", &output.name]).args(options);
+        }
+    }
+    let mut child = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("failed to start swaybg: {}", e))?;
+
+    // swaybg exits right away on an unreadable image; keep the old one then
+    tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+    if let Some(status) = child.try_wait()? {
+        anyhow::bail!("swaybg exited with {}; previous wallpaper kept", status);
+    }
+
+    if !old.is_empty() {
+        let _ = tokio::process::Command::new("kill")
+            .args(&old)
+            .output()
+            .await;
+    }
+    Ok(targets.len())
+}
+
+/// `hyprctl hyprpaper <args>`. hyprpaper answers "ok" on success and an
+/// error message otherwise, while hyprctl itself exits 0 either way.
+async fn hyprpaper(args: &[&str]) -> Result<()> {
+    let output = tokio::process::Command::new("hyprctl")
+        .arg("hyprpaper")
+        .args(args)
+        .output()
+        .await?;
+    let reply = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || reply != "ok" {
+        anyhow::bail!("hyprpaper {}: {}", args.join(" "), reply);
+    }
+    Ok(())
+}
+
+/// The raw reply to a hyprpaper query such as `listactive`.
+async fn hyprpaper_query(query: &str) -> String {
+    tokio::process::Command::new("hyprctl")
+        .args(["hyprpaper", query])
+        .output()
+        .await
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+/// Image per output from `listactive` ("DP-1 = /path" lines).
+fn parse_hyprpaper_active(reply: &str) -> HashMap<String, String> {
+    reply
+        .lines()
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(output, path)| (output.trim().to_owned(), path.trim().to_owned()))
+        .collect()
+}
+
+/// Preload images not loaded yet, assign them per output, then drop images
+/// no output uses anymore. Unless `force`, outputs already showing their
+/// image are skipped: like swww, hyprpaper doesn't report the fit. Returns
+/// the number of outputs updated.
+async fn apply_hyprpaper(targets: &[Target], force: bool) -> Result<usize> {
+    if !lmtt_modules::process_running("hyprpaper").await {
+        anyhow::bail!("hyprpaper is not running");
+    }
+
+    let active = if force {
+        HashMap::new()
+    } else {
+        parse_hyprpaper_active(&hyprpaper_query("listactive").await)
+    };
+    let loaded = hyprpaper_query("listloaded").await;
+    let mut updated = 0;
+    let mut failed = Vec::new();
+    for target in targets {
+        let path = target.path.to_string_lossy();
+        if active.get(&target.output).map(String::as_str) == Some(&*path) {
+            continue;
+        }
+        updated += 1;
+        if !loaded.lines().any(|line| line.trim() == path) {
+            if let Err(e) = hyprpaper(&["preload", &path]).await {
+                tracing::debug!("{}", e);
+            }
+        }
+        let assignment = format!(
+            "{},{}{}",
+            target.output,
+            hyprpaper_prefix(&target.fit),
+            path
+        );
+        if let Err(e) = hyprpaper(&["wallpaper", &assignment]).await {
+            failed.push(format!("{}: {}", target.output, e));
+        }
+    }
+    if updated > 0 {
+        if let Err(e) = hyprpaper(&["unload", "unused"]).await {
+            tracing::debug!("{}", e);
+        }
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!("hyprpaper rejected wallpapers for {}", failed.join("; "));
+    }
+    Ok(updated)
+}
+
+/// Show the registry's wallpapers on the desktop through the configured
+/// (or running) wallpaper daemon. Nothing happens when there is none.
+/// Outputs already showing their wallpaper are left alone, so a light/dark
+/// switch doesn't replay it; `force` re-sends every one.
+pub async fn apply_current(config: &WallpaperConfig, force: bool) -> Result<()> {
+    let Some(backend) = select_backend(config).await else {
+        tracing::debug!("no wallpaper daemon selected or running; wallpaper left alone");
+        return Ok(());
+    };
+
+    let outputs = live_outputs().await?;
+    let targets = resolve_targets(&outputs)?;
+    if targets.is_empty() {
+        tracing::debug!("no output has a configured wallpaper");
+        return Ok(());
+    }
+
+    let updated = match backend {
+        Backend::Swww => apply_swww(&targets, config, force).await?,
+        Backend::Swaybg => apply_swaybg(&targets, &outputs, force).await?,
+        Backend::Hyprpaper => apply_hyprpaper(&targets, force).await?,
+    };
+    if updated == 0 {
+        tracing::debug!("every output already shows its wallpaper");
+        return Ok(());
+    }
+    tracing::info!(
+        "Applied wallpaper to {} output(s) via {}",
+        updated,
+        backend.name()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_maps_to_each_backend() {
+        let fits = [Fit::Fill, Fit::Fit, Fit::Stretch, Fit::Center, Fit::Tile];
+        assert_eq!(
+            fits.map(|fit| swww_resize(&fit)),
+            ["crop", "fit", "crop", "no", "no"]
+        );
+        assert_eq!(
+            fits.map(|fit| swaybg_mode(&fit)),
+            ["fill", "fit", "stretch", "center", "tile"]
+        );
+        assert_eq!(
+            fits.map(|fit| hyprpaper_prefix(&fit)),
+            ["", "contain:", "", "contain:", "tile:"]
+        );
+    }
+
+    #[tokio::test]
+    async fn explicit_backends_are_used_as_configured() {
+        let mut config = WallpaperConfig::default();
+        for (backend, name) in [
+            (WallpaperBackend::Swww, Some("swww")),
+            (WallpaperBackend::Swaybg, Some("swaybg")),
+            (WallpaperBackend::Hyprpaper, Some("hyprpaper")),
+            (WallpaperBackend::None, None),
+        ] {
+            config.backend = backend;
+            assert_eq!(select_backend(&config).await.map(Backend::name), name);
+        }
+    }
+
+    #[test]
+    fn swaybg_options_are_grouped_by_output() {
+        let args: Vec<String> = [
+            "-c", "#000000", "-o", "DP-1", "-i", "/a.png", "-m", "fill", "-o", "HDMI-A-1",
+            "--image", "/b.png",
+        ]
+        .map(String::from)
+        .to_vec();
+        let outputs = swaybg_outputs(&args);
+        assert_eq!(outputs["*"], ["-c", "#000000"]);
+        assert_eq!(outputs["DP-1"], ["-i", "/a.png", "-m", "fill"]);
+        assert_eq!(outputs["HDMI-A-1"], ["--image", "/b.png"]);
+    }
+
+    #[test]
+    fn swaybg_instances_merge_global_then_output_options() {
+        let instances: Vec<Vec<String>> = [
+            vec!["-c", "#000000", "-o", "DP-1", "-i", "/a.png"],
+            vec!["-m", "fit", "-o", "HDMI-A-1", "-i", "/b.png"],
+        ]
+        .map(|args| args.into_iter().map(String::from).collect())
+        .to_vec();
+        let previous = previous_swaybg(&instances);
+        assert_eq!(previous["*"], ["-c", "#000000", "-m", "fit"]);
+        assert_eq!(
+            carried_options(&previous, "DP-1"),
+            ["-c", "#000000", "-m", "fit", "-i", "/a.png"]
+        );
+        assert_eq!(
+            carried_options(&previous, "eDP-1"),
+            ["-c", "#000000", "-m", "fit"]
+        );
+    }
+
+    #[test]
+    fn displayed_wallpapers_are_parsed() {
+        let swww = "DP-1: 2560x1440, scale: 1, currently displaying: image: /w/a.png\nHDMI-A-1: 1920x1080, scale: 1, currently displaying: color: 000000\n";
+        let displayed = parse_swww_query(swww);
+        assert_eq!(displayed.len(), 1);
+        assert_eq!(displayed["DP-1"], "/w/a.png");
+
+        let active = parse_hyprpaper_active("DP-1 = /w/a.png\nHDMI-A-1 = /w/b.png\n");
+        assert_eq!(active["HDMI-A-1"], "/w/b.png");
+        assert!(parse_hyprpaper_active("no wallpapers active").is_empty());
+    }
+
+    #[test]
+    fn swaybg_skips_outputs_showing_the_target() {
+        let target = Target {
+            output: "DP-1".into(),
+            path: "/w/a.png".into(),
+            fit: Fit::Fill,
+        };
+        let options = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(swaybg_shows(
+            &options(&["-m", "fit", "-i", "/w/a.png", "-m", "fill"]),
+            &target
+        ));
+        assert!(!swaybg_shows(
+            &options(&["-i", "/w/a.png", "-m", "fit"]),
+            &target
+        ));
+        assert!(!swaybg_shows(
+            &options(&["-i", "/w/b.png", "-m", "fill"]),
+            &target
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::Result;
+use lmtt_core::cache::Cache;
+use lmtt_core::{ColorScheme, Config, ThemeMode};
+use std::io::Write;
+use std::time::Duration;
+
+/// How often `--follow` re-reads the theme state. Every input is a small
+/// file in the cache/data dirs, so polling is cheap and needs no watcher.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Waybar custom-module payload (`"return-type": "json"`) for the current
+/// theme state:
+///
+/// - `text`: mode icon
+/// - `tooltip`: mode, wallpaper, scheme type, accent and the last switch's
+///   failures (pango markup, so every value is escaped)
+/// - `class`: `switching` while a switch holds the lock, `error` when the
+///   last switch had failed modules, otherwise the mode
+/// - `percentage`: share of modules the last switch updated
+///
+/// `tokens` is the last switch's tokens.json. It is written before modules
+/// run, so during a switch it already carries the target mode while
+/// theme_state still has the old one.
+pub async fn render(
+    config: &Config,
+    cache: &Cache,
+    tokens: Option<&ColorScheme>,
+) -> Result<serde_json::Value> {
+    let mode = cache.get_theme_state(config.general.default_mode).await?;
+    let switching = switch_in_progress(cache);
+    let last = cache.get_last_switch().await;
+
+    let failures = last.as_ref().map(|l| l.failed.as_slice()).unwrap_or(&[]);
+    let percentage = match &last {
+        Some(l) if l.succeeded + l.failed.len() > 0 => {
+            l.succeeded * 100 / (l.succeeded + l.failed.len())
+        }
+        _ => 100,
+    };
+
+    let class = if switching {
+        "switching".to_string()
+    } else if !failures.is_empty() {
+        "error".to_string()
+    } else {
+        mode.to_string()
+    };
+
+    let text = if switching {
+        "◐"
+    } else {
+        match mode {
+            ThemeMode::Light => "☀",
+            ThemeMode::Dark => "☾",
+        }
+    };
+
+    let mut tooltip = match (tokens, switching) {
+        (Some(t), true) if t.mode != mode => format!("Switching to {} mode", t.mode),
+        _ => format!("Theme: {}", mode),
+    };
+    tooltip.push_str(&format!(
+        "\nWallpaper: {}",
+        markup_escape(&config.general.wallpaper)
+    ));
+    tooltip.push_str(&format!(
+        "\nScheme: {}",
+        markup_escape(&config.general.scheme_type)
+    ));
+    if let Some(primary) = tokens.and_then(|t| t.primary()) {
+        tooltip.push_str(&format!("\nAccent: {}", markup_escape(primary)));
+    }
+    if !failures.is_empty() {
+        tooltip.push_str(&format!("\nLast switch: {} failed", failures.len()));
+        for failure in failures {
+            tooltip.push_str(&format!(
+                "\n  ✗ {}: {}",
+                markup_escape(&failure.name),
+                markup_escape(&failure.error)
+            ));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "text": text,
+        "tooltip": tooltip,
+        "class": class,
+        "percentage": percentage,
+    }))
+}
+
+/// Print one line now and a new line whenever the rendered state changes.
+/// Returns cleanly when the bar closes its end of the pipe.
+pub async fn follow() -> Result<()> {
+    let mut config = Config::load()?;
+    let mut previous = None;
+    loop {
+        // Re-read config each tick so `lmtt wallpaper set` and config edits
+        // show up; a config caught mid-edit keeps the last good one.
+        if let Ok(fresh) = Config::load() {
+            config = fresh;
+        }
+        let cache = Cache::from_config(&config)?;
+        let tokens = lmtt_core::tokens::load_current().ok();
+        let payload = render(&config, &cache, tokens.as_ref()).await?;
+        if previous.as_ref() != Some(&payload) {
+            let mut stdout = std::io::stdout().lock();
+            match writeln!(stdout, "{}", serde_json::to_string(&payload)?)
+                .and_then(|()| stdout.flush())
+            {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }
+            previous = Some(payload);
+        }
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+    }
+}
+
+/// Whether another process currently holds the switch lock. Probing takes
+/// the lock for an instant when it is free, which is harmless: a switch that
+/// starts at that moment just waits for us to drop it.
+fn switch_in_progress(cache: &Cache) -> bool {
+    let Ok(file) = std::fs::File::open(cache.lock_path()) else {
+        return false;
+    };
+    matches!(file.try_lock(), Err(std::fs::TryLockError::WouldBlock))
+}
+
+/// Waybar renders tooltips as pango markup; a bare `&` or `<` in a path or
+/// error message would make the whole tooltip fail to parse.
+fn markup_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmtt_core::cache::{FailedModule, SwitchRecord};
+
+    #[tokio::test]
+    async fn render_reports_mode_and_failures() {
+        let dir = std::env::temp_dir().join(format!("lmtt-waybar-test-{}", std::process::id()));
+        let cache = Cache::new(dir.clone()).unwrap();
+        let mut config = Config::default();
+        config.general.wallpaper = "~/walls/a&b.png".into();
+
+        cache.set_theme_state(ThemeMode::Dark).await.unwrap();
+        let dark = render(&config, &cache, None).await.unwrap();
+        assert_eq!(dark["text"], "☾");
+        assert_eq!(dark["class"], "dark");
+        assert_eq!(dark["percentage"], 100);
+        let tooltip = dark["tooltip"].as_str().unwrap();
+        assert!(tooltip.starts_with("Theme: dark\nWallpaper: ~/walls/a&amp;b.png\n"));
+
+        cache.set_theme_state(ThemeMode::Light).await.unwrap();
+        cache
+            .set_last_switch(&SwitchRecord {
+                mode: ThemeMode::Light,
+                succeeded: 3,
+                failed: vec![FailedModule {
+                    name: "waybar".into(),
+                    error: "<timeout>".into(),
+                }],
+            })
+            .await
+            .unwrap();
+        let mut tokens = ColorScheme::new(ThemeMode::Light);
+        tokens.set("primary".into(), "#3a5f8a".into());
+        let light = render(&config, &cache, Some(&tokens)).await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+        assert_eq!(light["text"], "☀");
+        assert_eq!(light["class"], "error");
+        assert_eq!(light["percentage"], 75);
+        let tooltip = light["tooltip"].as_str().unwrap();
+        assert!(tooltip.starts_with("Theme: light\n"));
+        assert!(tooltip.contains("\nAccent: #3a5f8a\n"));
+        assert!(tooltip.ends_with("\nLast switch: 1 failed\n  ✗ waybar: &lt;timeout&gt;"));
+    }
+}